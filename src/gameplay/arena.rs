@@ -0,0 +1,320 @@
+//! Arenas generated from a seed.
+
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use hexx::Hex;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IteratorRandom};
+
+use crate::gameplay::{
+    level::{ActiveLevel, Level, MapDef, TileDef},
+    mode::RunSeed,
+    terrain::Terrain,
+};
+
+pub(crate) struct ArenaPlugin;
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Arena>()
+            .init_resource::<ArenaSeed>()
+            .init_resource::<ArenaSettings>();
+    }
+}
+
+/// Where the arena that gets played comes from
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) enum Arena {
+    /// the authored level
+    #[default]
+    Level,
+    /// generated from the [`ArenaSeed`]
+    Generated,
+}
+
+impl Arena {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Arena::Level => "Level",
+            Arena::Generated => "Generated",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Arena::Level => Arena::Generated,
+            Arena::Generated => Arena::Level,
+        }
+    }
+}
+
+/// Seed generated arenas are built from. Kept between games so an arena can be replayed.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct ArenaSeed(pub u64);
+
+impl Default for ArenaSeed {
+    fn default() -> Self {
+        Self(rand::random())
+    }
+}
+
+impl ArenaSeed {
+    pub fn reroll(&mut self) {
+        self.0 = rand::random();
+    }
+}
+
+/// Knobs for the arena generator
+#[derive(Resource)]
+pub(crate) struct ArenaSettings {
+    pub radius: u32,
+    pub hex_size: f32,
+    /// how far the outline can cut into the map, as a fraction of the radius
+    pub outline_roughness: f32,
+    /// hexes around the center that are always open so the player has room to start
+    pub clear_radius: u32,
+    pub obstacle_clusters: usize,
+    pub obstacle_size: (usize, usize),
+    pub terrain_patches: usize,
+    pub patch_size: (usize, usize),
+}
+
+impl Default for ArenaSettings {
+    fn default() -> Self {
+        Self {
+            radius: 8,
+            hex_size: 50.0,
+            outline_roughness: 0.35,
+            clear_radius: 2,
+            obstacle_clusters: 8,
+            obstacle_size: (2, 6),
+            terrain_patches: 10,
+            patch_size: (3, 10),
+        }
+    }
+}
+
+/// Pick the level for the next game, generating a new arena if needed
+pub(crate) fn prepare_arena(
+    arena: Res<Arena>,
+    arena_seed: Res<ArenaSeed>,
+    settings: Res<ArenaSettings>,
+    assets: Res<AssetServer>,
+    mut levels: ResMut<Assets<Level>>,
+    mut active: ResMut<ActiveLevel>,
+    mut seed: ResMut<RunSeed>,
+) {
+    match *arena {
+        Arena::Level => {
+            seed.0 = None;
+            active.reset(&assets);
+        }
+        Arena::Generated => {
+            seed.0 = Some(arena_seed.0);
+            active.handle = levels.add(generate(arena_seed.0, &settings));
+        }
+    }
+}
+
+/// Terrain patches are picked from these
+const PATCH_TERRAIN: [Terrain; 4] = [Terrain::Grass, Terrain::Mud, Terrain::Ice, Terrain::Boost];
+
+/// Build a level with a random arena. The same seed and settings always give the same arena.
+pub(crate) fn generate(seed: u64, settings: &ArenaSettings) -> Level {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let radius = settings.radius;
+
+    let mut open = outline(&mut rng, settings);
+    let mut terrain = HashMap::<Hex, Terrain>::default();
+    let clear = |hex: &Hex| hex.ulength() <= settings.clear_radius;
+
+    for _ in 0..settings.obstacle_clusters {
+        let size = rng.gen_range(settings.obstacle_size.0..=settings.obstacle_size.1);
+        let Some(start) = sorted(&open)
+            .filter(|hex| !clear(hex) && !terrain.contains_key(hex))
+            .choose(&mut rng)
+        else {
+            break;
+        };
+        for hex in grow(&mut rng, start, size, |hex| {
+            open.contains(hex) && !clear(hex)
+        }) {
+            terrain.insert(hex, Terrain::Wall);
+        }
+    }
+
+    for _ in 0..settings.terrain_patches {
+        let size = rng.gen_range(settings.patch_size.0..=settings.patch_size.1);
+        let kind = PATCH_TERRAIN[rng.gen_range(0..PATCH_TERRAIN.len())];
+        let Some(start) = sorted(&open)
+            .filter(|hex| !terrain.contains_key(hex))
+            .choose(&mut rng)
+        else {
+            break;
+        };
+        let walkable = |hex: &Hex| open.contains(hex) && terrain.get(hex) != Some(&Terrain::Wall);
+        for hex in grow(&mut rng, start, size, walkable) {
+            terrain.insert(hex, kind);
+        }
+    }
+
+    connect_regions(&mut open, &mut terrain);
+
+    // sort so the level file is stable for a seed
+    let mut removed = Hex::ZERO
+        .range(radius)
+        .filter(|hex| !open.contains(hex))
+        .map(|hex| hex.to_array())
+        .collect::<Vec<_>>();
+    removed.sort();
+    let mut tiles = terrain
+        .into_iter()
+        .map(|(hex, terrain)| TileDef {
+            hex: hex.to_array(),
+            terrain,
+        })
+        .collect::<Vec<_>>();
+    tiles.sort_by_key(|tile| tile.hex);
+
+    Level {
+        name: format!("Arena {seed}"),
+        map: MapDef {
+            radius,
+            removed,
+            hex_size: settings.hex_size,
+            tiles,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// hexes in a stable order, so generation doesn't depend on hash order
+fn sorted(hexes: &HashSet<Hex>) -> impl Iterator<Item = Hex> {
+    let mut hexes = hexes.iter().copied().collect::<Vec<_>>();
+    hexes.sort_by_key(|hex| hex.to_array());
+    hexes.into_iter()
+}
+
+/// hexes inside an irregular outline around the center
+fn outline(rng: &mut impl Rng, settings: &ArenaSettings) -> HashSet<Hex> {
+    // a few overlapping waves around the edge
+    let waves = (0..3)
+        .map(|i| {
+            let frequency = rng.gen_range(2..=4 + i * 2) as f32;
+            let phase = rng.gen_range(0.0..TAU);
+            let amplitude = rng.gen_range(0.3..1.0);
+            (frequency, phase, amplitude)
+        })
+        .collect::<Vec<_>>();
+    let total_amplitude = waves.iter().map(|(_, _, a)| a).sum::<f32>();
+    let radius = settings.radius as f32;
+
+    let layout = MapDef {
+        hex_size: settings.hex_size,
+        ..default()
+    }
+    .layout();
+    Hex::ZERO
+        .range(settings.radius)
+        .filter(|hex| {
+            if hex.ulength() <= settings.clear_radius {
+                return true;
+            }
+            let angle = layout.hex_to_world_pos(*hex).to_angle();
+            // 0 to 1, how far the outline is pulled in at this angle
+            let cut = waves
+                .iter()
+                .map(|(f, p, a)| a * (0.5 + 0.5 * (angle * f + p).sin()))
+                .sum::<f32>()
+                / total_amplitude;
+            let edge = radius * (1.0 - settings.outline_roughness * cut);
+            hex.length() as f32 <= edge
+        })
+        .collect()
+}
+
+/// random connected group of up to `size` hexes starting from `start`
+fn grow(rng: &mut impl Rng, start: Hex, size: usize, allowed: impl Fn(&Hex) -> bool) -> Vec<Hex> {
+    let mut cluster = vec![start];
+    while cluster.len() < size {
+        let next = cluster
+            .iter()
+            .flat_map(|hex| hex.all_neighbors())
+            .filter(|hex| allowed(hex) && !cluster.contains(hex))
+            .choose(rng);
+        match next {
+            Some(hex) => cluster.push(hex),
+            None => break,
+        }
+    }
+    cluster
+}
+
+/// hexes that can be reached from the center without crossing walls
+fn reachable(open: &HashSet<Hex>, terrain: &HashMap<Hex, Terrain>) -> HashSet<Hex> {
+    let passable = |hex: &Hex| open.contains(hex) && terrain.get(hex) != Some(&Terrain::Wall);
+    let mut reached = HashSet::from_iter([Hex::ZERO]);
+    let mut queue = VecDeque::from([Hex::ZERO]);
+    while let Some(hex) = queue.pop_front() {
+        for neighbor in hex.all_neighbors() {
+            if passable(&neighbor) && reached.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    reached
+}
+
+/// carve passages until every open hex can be reached from the center
+fn connect_regions(open: &mut HashSet<Hex>, terrain: &mut HashMap<Hex, Terrain>) {
+    loop {
+        let reached = reachable(open, terrain);
+        let Some(lost) = sorted(open)
+            .find(|hex| !reached.contains(hex) && terrain.get(hex) != Some(&Terrain::Wall))
+        else {
+            return;
+        };
+
+        let nearest = sorted(&reached)
+            .min_by_key(|hex| hex.unsigned_distance_to(lost))
+            .unwrap_or(Hex::ZERO);
+        for hex in lost.line_to(nearest) {
+            open.insert(hex);
+            if terrain.get(&hex) == Some(&Terrain::Wall) {
+                terrain.remove(&hex);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_floor_tile_is_reachable_from_the_spawn() {
+        let settings = ArenaSettings::default();
+        for seed in [0, 1, 7, 42, 1234, 98765, u64::MAX] {
+            let map = generate(seed, &settings).map;
+            let floor = |hex: &Hex| map.contains_hex(*hex) && !map.is_wall(*hex);
+            assert!(floor(&Hex::ZERO), "seed {seed}: the spawn is blocked");
+
+            let mut reached = HashSet::<Hex>::from_iter([Hex::ZERO]);
+            let mut queue = VecDeque::from([Hex::ZERO]);
+            while let Some(hex) = queue.pop_front() {
+                for neighbor in hex.all_neighbors() {
+                    if floor(&neighbor) && reached.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            for hex in map.hexes().filter(floor) {
+                assert!(reached.contains(&hex), "seed {seed}: {hex:?} is cut off");
+            }
+        }
+    }
+}
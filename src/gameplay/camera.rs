@@ -0,0 +1,225 @@
+//! Camera that follows the player around arenas bigger than the window.
+
+use bevy::{
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    math::StableInterpolate,
+    prelude::*,
+    render::camera::ScalingMode,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    gameplay::{
+        decoration::UnderCanopy,
+        enemy::{Enemy, EnemyType},
+        level::CurrentLevel,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct CameraPlugin;
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraSettings>()
+            .init_resource::<CameraZoom>()
+            .add_systems(OnEnter(Screen::Gameplay), (fit_zoom, snap_to_start).chain())
+            .add_systems(
+                Update,
+                (
+                    fit_zoom.run_if(on_event::<WindowResized>),
+                    zoom_camera.in_set(AppSystems::RecordInput),
+                    (follow_camera, draw_edge_indicators)
+                        .chain()
+                        .after(AppSystems::Update)
+                        .in_set(PausableSystems),
+                )
+                    .run_if(in_state(Screen::Gameplay)),
+            )
+            .add_systems(OnExit(Screen::Gameplay), reset_camera);
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct CameraSettings {
+    /// fraction of the view around the center the player can move in without the camera following
+    pub dead_zone: f32,
+    /// how far ahead of the player the camera looks in the direction they are aiming
+    pub look_ahead: f32,
+    /// how quickly the camera catches up, higher is snappier
+    pub smoothing: f32,
+    /// smallest projection scale, zoomed in the most
+    pub min_zoom: f32,
+    /// largest projection scale, zoomed out the most
+    pub max_zoom: f32,
+    /// largest scale used to fit the arena in the window, bigger arenas scroll instead
+    pub max_fit_zoom: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.15,
+            look_ahead: 120.0,
+            smoothing: 4.0,
+            min_zoom: 0.75,
+            max_zoom: 2.0,
+            max_fit_zoom: 1.0,
+        }
+    }
+}
+
+/// Projection scale the camera is easing towards
+#[derive(Resource, Default)]
+pub(crate) struct CameraZoom(f32);
+
+/// Space around the arena, in hexes
+const MARGIN: f32 = 0.5;
+/// How far inside the edge of the view off screen shapes are marked
+const INDICATOR_INSET: f32 = 16.0;
+
+/// area the camera can show, a little bigger than the map
+fn arena_bounds(level: &CurrentLevel) -> Rect {
+    let map = &level.get().map;
+    map.bounds().inflate(map.hex_size * MARGIN)
+}
+
+/// zoom in on arenas smaller than the window, bigger arenas are followed instead of shrunk to fit
+fn fit_zoom(
+    level: CurrentLevel,
+    settings: Res<CameraSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut zoom: ResMut<CameraZoom>,
+    mut projection: Single<&mut Projection, With<Camera2d>>,
+) {
+    let bounds = arena_bounds(&level);
+    let fit = (bounds.size() / window.size()).max_element();
+    zoom.0 = fit
+        .min(settings.max_fit_zoom)
+        .clamp(settings.min_zoom, settings.max_zoom);
+    if let Projection::Orthographic(orthographic) = &mut **projection {
+        orthographic.scaling_mode = ScalingMode::WindowSize;
+        orthographic.scale = zoom.0;
+    }
+}
+
+/// the player always starts in the middle of the map
+fn snap_to_start(
+    level: CurrentLevel,
+    window: Single<&Window, With<PrimaryWindow>>,
+    zoom: Res<CameraZoom>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    let half_view = window.size() / 2.0 * zoom.0;
+    let center = clamp_to_arena(Vec2::ZERO, half_view, arena_bounds(&level));
+    camera.translation = center.extend(camera.translation.z);
+}
+
+fn zoom_camera(
+    scroll: Res<AccumulatedMouseScroll>,
+    settings: Res<CameraSettings>,
+    mut zoom: ResMut<CameraZoom>,
+) {
+    let step = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y * 0.1,
+        MouseScrollUnit::Pixel => scroll.delta.y * 0.002,
+    };
+    if step != 0.0 {
+        zoom.0 = (zoom.0 * (1.0 - step)).clamp(settings.min_zoom, settings.max_zoom);
+    }
+}
+
+pub(crate) fn follow_camera(
+    time: Res<Time>,
+    level: CurrentLevel,
+    settings: Res<CameraSettings>,
+    zoom: Res<CameraZoom>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    player: Single<&Transform, (With<Player>, Without<Camera2d>)>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+    let dt = time.delta_secs();
+
+    let scale = match &mut *projection {
+        Projection::Orthographic(orthographic) => {
+            orthographic
+                .scale
+                .smooth_nudge(&zoom.0, settings.smoothing, dt);
+            orthographic.scale
+        }
+        _ => 1.0,
+    };
+    let half_view = window.size() / 2.0 * scale;
+
+    let aim = (player.rotation * Vec3::Y).truncate();
+    let target = player.translation.truncate() + aim * settings.look_ahead;
+
+    // only follow once the target leaves the dead zone
+    let camera_center = transform.translation.truncate();
+    let dead_zone = half_view * settings.dead_zone;
+    let offset = target - camera_center;
+    let outside = offset - offset.clamp(-dead_zone, dead_zone);
+    let mut center = camera_center;
+    center.smooth_nudge(&(camera_center + outside), settings.smoothing, dt);
+
+    let center = clamp_to_arena(center, half_view, arena_bounds(&level));
+    transform.translation = center.extend(transform.translation.z);
+}
+
+/// keep the view inside the arena, centering on any axis the arena is smaller than the view
+fn clamp_to_arena(center: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + half_view;
+    let max = bounds.max - half_view;
+    Vec2::new(
+        if min.x <= max.x {
+            center.x.clamp(min.x, max.x)
+        } else {
+            bounds.center().x
+        },
+        if min.y <= max.y {
+            center.y.clamp(min.y, max.y)
+        } else {
+            bounds.center().y
+        },
+    )
+}
+
+/// arrows at the edge of the view pointing at shapes that are off screen
+fn draw_edge_indicators(
+    mut gizmos: Gizmos,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Transform, &Projection), With<Camera2d>>,
+    shapes: Query<(&Transform, &EnemyType), (With<Enemy>, Without<UnderCanopy>, Without<Camera2d>)>,
+) {
+    let (camera, projection) = *camera;
+    let scale = match projection {
+        Projection::Orthographic(orthographic) => orthographic.scale,
+        _ => 1.0,
+    };
+    let center = camera.translation.truncate();
+    let half_view = window.size() / 2.0 * scale;
+    let inset = (half_view - INDICATOR_INSET * scale).max(Vec2::ZERO);
+
+    for (transform, enemy_type) in &shapes {
+        let offset = transform.translation.truncate() - center;
+        if offset.abs().cmple(half_view).all() {
+            continue;
+        }
+        // scale the offset down until it touches the inset edge
+        let t = (inset / offset.abs()).min_element();
+        let tip = center + offset * t;
+        let direction = offset.normalize_or_zero();
+        gizmos.arrow_2d(tip - direction * 20.0 * scale, tip, enemy_type.color());
+    }
+}
+
+fn reset_camera(camera: Single<(&mut Transform, &mut Projection), With<Camera2d>>) {
+    let (mut transform, mut projection) = camera.into_inner();
+    transform.translation = Vec3::ZERO.with_z(transform.translation.z);
+    if let Projection::Orthographic(orthographic) = &mut *projection {
+        orthographic.scaling_mode = ScalingMode::WindowSize;
+        orthographic.scale = 1.0;
+    }
+}
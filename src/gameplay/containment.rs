@@ -0,0 +1,318 @@
+//! Deciding which shapes a closed loop surrounds.
+
+use std::f32::consts::TAU;
+
+use avian2d::prelude::Collider;
+use bevy::prelude::*;
+
+pub(crate) struct ContainmentPlugin;
+impl Plugin for ContainmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ContainmentSettings>();
+    }
+}
+
+/// Controls how much of a shape needs to be inside a loop for it to count
+#[derive(Resource)]
+pub(crate) struct ContainmentSettings {
+    /// fraction of the shape's area that must be inside the loop to be surrounded
+    pub threshold: f32,
+    /// shapes with an overlap between this and `threshold` are borderline.
+    /// anything less than this is ignored
+    pub borderline: f32,
+    /// what to do with borderline shapes
+    pub borderline_action: BorderlineAction,
+    /// speed borderline shapes are pushed out of the loop with
+    pub push_speed: f32,
+}
+
+impl Default for ContainmentSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.6,
+            borderline: 0.2,
+            borderline_action: BorderlineAction::Squeeze,
+            push_speed: 300.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum BorderlineAction {
+    /// treat borderline shapes as outside the loop
+    Ignore,
+    /// treat borderline shapes as inside the loop
+    Squeeze,
+    /// kick borderline shapes out of the loop
+    Push,
+}
+
+impl BorderlineAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BorderlineAction::Ignore => "Ignore",
+            BorderlineAction::Squeeze => "Squeeze in",
+            BorderlineAction::Push => "Push out",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            BorderlineAction::Ignore => BorderlineAction::Squeeze,
+            BorderlineAction::Squeeze => BorderlineAction::Push,
+            BorderlineAction::Push => BorderlineAction::Ignore,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Containment {
+    Inside,
+    Outside,
+    /// shape should be pushed out of the loop
+    PushOut,
+}
+
+impl ContainmentSettings {
+    /// classify a shape by the fraction of its area inside the loop
+    pub fn classify(&self, fraction: f32) -> Containment {
+        if fraction >= self.threshold {
+            Containment::Inside
+        } else if fraction < self.borderline {
+            Containment::Outside
+        } else {
+            match self.borderline_action {
+                BorderlineAction::Ignore => Containment::Outside,
+                BorderlineAction::Squeeze => Containment::Inside,
+                BorderlineAction::Push => Containment::PushOut,
+            }
+        }
+    }
+}
+
+/// Fraction of the collider's area that overlaps the loop `polygon`.
+pub(crate) fn overlap_fraction(
+    polygon: &[Vec2],
+    collider: &Collider,
+    transform: &Transform,
+) -> f32 {
+    let shape = collider_outline(collider, transform);
+    let shape_area = signed_area(&shape).abs();
+    if shape_area <= f32::EPSILON {
+        return 0.0;
+    }
+
+    let overlap = clip_to_convex(polygon, &shape);
+    (signed_area(&overlap).abs() / shape_area).clamp(0.0, 1.0)
+}
+
+/// Area enclosed by the polygon
+pub(crate) fn area(polygon: &[Vec2]) -> f32 {
+    signed_area(polygon).abs()
+}
+
+/// Whether the point is inside the polygon, by counting edge crossings of a ray going right
+pub(crate) fn contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Average of the polygon's points. Good enough for pushing things away from the middle of a loop.
+pub(crate) fn centroid(polygon: &[Vec2]) -> Vec2 {
+    if polygon.is_empty() {
+        return Vec2::ZERO;
+    }
+    polygon.iter().sum::<Vec2>() / polygon.len() as f32
+}
+
+/// Convex outline of the collider in world space.
+fn collider_outline(collider: &Collider, transform: &Transform) -> Vec<Vec2> {
+    // number of points used to approximate circles
+    const CIRCLE_SEGMENTS: usize = 16;
+
+    let shape = collider.shape_scaled();
+    let local = if let Some(ball) = shape.as_ball() {
+        (0..CIRCLE_SEGMENTS)
+            .map(|i| ball.radius * Vec2::from_angle(TAU * i as f32 / CIRCLE_SEGMENTS as f32))
+            .collect()
+    } else if let Some(cuboid) = shape.as_cuboid() {
+        let half = Vec2::new(cuboid.half_extents.x, cuboid.half_extents.y);
+        vec![
+            Vec2::new(-half.x, -half.y),
+            Vec2::new(half.x, -half.y),
+            Vec2::new(half.x, half.y),
+            Vec2::new(-half.x, half.y),
+        ]
+    } else if let Some(triangle) = shape.as_triangle() {
+        [triangle.a, triangle.b, triangle.c]
+            .iter()
+            .map(|p| Vec2::new(p.x, p.y))
+            .collect()
+    } else if let Some(polygon) = shape.as_convex_polygon() {
+        polygon
+            .points()
+            .iter()
+            .map(|p| Vec2::new(p.x, p.y))
+            .collect()
+    } else {
+        // fall back to the bounding box for anything else
+        let aabb = shape.compute_local_aabb();
+        vec![
+            Vec2::new(aabb.mins.x, aabb.mins.y),
+            Vec2::new(aabb.maxs.x, aabb.mins.y),
+            Vec2::new(aabb.maxs.x, aabb.maxs.y),
+            Vec2::new(aabb.mins.x, aabb.maxs.y),
+        ]
+    };
+
+    let rotation = transform.rotation.to_euler(EulerRot::XYZ).2;
+    let rotation = Vec2::from_angle(rotation);
+    let translation = transform.translation.truncate();
+    local
+        .into_iter()
+        .map(|p| translation + rotation.rotate(p))
+        .collect()
+}
+
+/// Shoelace formula. Positive for counter clockwise polygons.
+fn signed_area(polygon: &[Vec2]) -> f32 {
+    if polygon.len() < 3 {
+        return 0.0;
+    }
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.perp_dot(*b))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Sutherland-Hodgman clipping of an arbitrary `subject` polygon against a convex `clip` polygon.
+/// The result may contain degenerate edges when the subject is concave, but its area is correct.
+fn clip_to_convex(subject: &[Vec2], clip: &[Vec2]) -> Vec<Vec2> {
+    let mut clip = clip.to_vec();
+    if signed_area(&clip) < 0.0 {
+        clip.reverse();
+    }
+
+    let mut output = subject.to_vec();
+    for (&edge_start, &edge_end) in clip.iter().zip(clip.iter().cycle().skip(1)) {
+        if output.is_empty() {
+            break;
+        }
+        let edge = edge_end - edge_start;
+        let inside = |p: Vec2| edge.perp_dot(p - edge_start) >= 0.0;
+        let intersection = |a: Vec2, b: Vec2| {
+            let ab = b - a;
+            let denom = edge.perp_dot(ab);
+            if denom.abs() <= f32::EPSILON {
+                return a;
+            }
+            let t = edge.perp_dot(edge_start - a) / denom;
+            a + ab * t
+        };
+
+        let input = std::mem::take(&mut output);
+        for (&current, &next) in input.iter().zip(input.iter().cycle().skip(1)) {
+            match (inside(current), inside(next)) {
+                (true, true) => output.push(next),
+                (true, false) => output.push(intersection(current, next)),
+                (false, true) => {
+                    output.push(intersection(current, next));
+                    output.push(next);
+                }
+                (false, false) => {}
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 100 by 100 square loop around the origin
+    fn square_loop() -> Vec<Vec2> {
+        vec![
+            Vec2::new(-50.0, -50.0),
+            Vec2::new(50.0, -50.0),
+            Vec2::new(50.0, 50.0),
+            Vec2::new(-50.0, 50.0),
+        ]
+    }
+
+    /// L shaped loop with the top right corner missing
+    fn l_loop() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 40.0),
+            Vec2::new(40.0, 40.0),
+            Vec2::new(40.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ]
+    }
+
+    fn fraction_at(polygon: &[Vec2], position: Vec2) -> f32 {
+        overlap_fraction(
+            polygon,
+            &Collider::rectangle(10.0, 10.0),
+            &Transform::from_translation(position.extend(0.0)),
+        )
+    }
+
+    #[test]
+    fn shape_fully_inside() {
+        assert!((fraction_at(&square_loop(), Vec2::ZERO) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shape_fully_outside() {
+        assert!(fraction_at(&square_loop(), Vec2::new(200.0, 0.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shape_half_inside() {
+        assert!((fraction_at(&square_loop(), Vec2::new(50.0, 0.0)) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn clockwise_loop_gives_the_same_fraction() {
+        let mut polygon = square_loop();
+        polygon.reverse();
+        assert!((fraction_at(&polygon, Vec2::new(50.0, 0.0)) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn concave_loop() {
+        let polygon = l_loop();
+        // in both arms of the L
+        assert!((fraction_at(&polygon, Vec2::new(80.0, 20.0)) - 1.0).abs() < 1e-4);
+        assert!((fraction_at(&polygon, Vec2::new(20.0, 80.0)) - 1.0).abs() < 1e-4);
+        // in the missing corner
+        assert!(fraction_at(&polygon, Vec2::new(70.0, 70.0)).abs() < 1e-4);
+        // straddling the inner corner, only the bottom left quarter is inside
+        assert!((fraction_at(&polygon, Vec2::new(40.0, 40.0)) - 0.75).abs() < 1e-3);
+    }
+
+    #[test]
+    fn clip_keeps_the_overlap() {
+        let clip = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ];
+        let overlap = clip_to_convex(&square_loop(), &clip);
+        assert!((signed_area(&overlap).abs() - 2500.0).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,177 @@
+//! Enemies that hunt paths and cut them.
+
+use avian2d::prelude::{
+    Collider, CollisionEventsEnabled, CollisionStarted, ComputedMass, ExternalForce, LinearDamping,
+    MaxLinearSpeed, RigidBody,
+};
+use bevy::{color::palettes::tailwind, prelude::*};
+
+use crate::{
+    gameplay::{
+        enemy_ai::follow_player,
+        layers,
+        level::CurrentLevel,
+        path::{CutPath, DrawPath, Path, PathSegment},
+        pathfinding::{NavGrid, Route},
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct CutterPlugin;
+impl Plugin for CutterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CutterHandles>()
+            .add_event::<SpawnCutters>()
+            .add_systems(
+                Update,
+                (spawn_cutters, cut_on_contact).run_if(in_state(Screen::Gameplay)),
+            )
+            .add_systems(
+                FixedUpdate,
+                seek_paths
+                    .after(follow_player)
+                    .run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// Enemy that goes for the path being drawn and cuts any path it touches
+#[derive(Component)]
+#[require(Route, CutCooldown)]
+pub(crate) struct PathCutter;
+
+/// Time until a cutter can cut again, so staying on a path splits it once
+/// instead of eating it a segment at a time
+#[derive(Component)]
+struct CutCooldown(Timer);
+
+impl Default for CutCooldown {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(CUT_COOLDOWN, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+}
+
+/// Spawn path cutters inside the level's spawn area
+#[derive(Event)]
+pub(crate) struct SpawnCutters {
+    pub count: usize,
+}
+
+#[derive(Resource)]
+struct CutterHandles {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for CutterHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(RegularPolygon::new(CUTTER_RADIUS, 4));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::Srgba(tailwind::ROSE_500));
+        Self { mesh, material }
+    }
+}
+
+const CUTTER_RADIUS: f32 = 10.0;
+/// acceleration towards the path
+const SEEK_ACCELERATION: f32 = 300.0;
+/// acceleration towards the player when nothing is being drawn
+const IDLE_ACCELERATION: f32 = 60.0;
+/// seconds between cuts by the same cutter
+const CUT_COOLDOWN: f32 = 1.0;
+
+fn spawn_cutters(
+    mut commands: Commands,
+    mut events: EventReader<SpawnCutters>,
+    level: CurrentLevel,
+    handles: Res<CutterHandles>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in events.read() {
+        for _ in 0..event.count {
+            let position = level.get().map.random_spawn_position(&mut rng);
+            commands.spawn((
+                Name::new("Path Cutter"),
+                PathCutter,
+                Mesh2d(handles.mesh.clone()),
+                MeshMaterial2d(handles.material.clone()),
+                RigidBody::Dynamic,
+                Collider::circle(CUTTER_RADIUS),
+                CollisionEventsEnabled,
+                LinearDamping(1.0),
+                MaxLinearSpeed(250.0),
+                ExternalForce::ZERO.with_persistence(false),
+                Transform::from_translation(position.extend(layers::ON_GROUND)),
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+/// head for the closest point of the path being drawn, or drift towards the player
+fn seek_paths(
+    nav: Res<NavGrid>,
+    pen: Single<(&DrawPath, &Transform), With<Player>>,
+    paths: Query<&Path>,
+    mut cutters: Query<
+        (&Transform, &ComputedMass, &mut ExternalForce, &mut Route),
+        With<PathCutter>,
+    >,
+) {
+    let (draw, pen_transform) = *pen;
+    let live = draw.path().and_then(|path| paths.get(path).ok());
+    for (transform, mass, mut force, mut route) in &mut cutters {
+        let position = transform.translation.truncate();
+        let (target, acceleration) = match live.and_then(|path| closest_point(path, position)) {
+            Some(point) => (point, SEEK_ACCELERATION),
+            None => (pen_transform.translation.truncate(), IDLE_ACCELERATION),
+        };
+        let direction = (route.waypoint(&nav, position, target) - position).normalize_or_zero();
+        force.apply_force(direction * acceleration * mass.value());
+    }
+}
+
+fn closest_point(path: &Path, position: Vec2) -> Option<Vec2> {
+    path.points().iter().copied().min_by(|a, b| {
+        a.distance_squared(position)
+            .total_cmp(&b.distance_squared(position))
+    })
+}
+
+fn cut_on_contact(
+    time: Res<Time>,
+    mut started: EventReader<CollisionStarted>,
+    mut cutters: Query<&mut CutCooldown, With<PathCutter>>,
+    segments: Query<&ChildOf, With<PathSegment>>,
+    mut cuts: EventWriter<CutPath>,
+) {
+    for mut cooldown in &mut cutters {
+        cooldown.0.tick(time.delta());
+    }
+
+    for CollisionStarted(a, b) in started.read() {
+        let (cutter, segment) = if cutters.contains(*a) {
+            (*a, *b)
+        } else {
+            (*b, *a)
+        };
+        let (Ok(mut cooldown), Ok(child_of)) = (cutters.get_mut(cutter), segments.get(segment))
+        else {
+            continue;
+        };
+        if !cooldown.0.finished() {
+            continue;
+        }
+        cooldown.0.reset();
+        cuts.write(CutPath {
+            path: child_of.parent(),
+            segment,
+        });
+    }
+}
@@ -0,0 +1,269 @@
+//! Tree canopies and clouds drawn above the play area.
+
+use bevy::{color::palettes::tailwind, math::StableInterpolate, prelude::*};
+use hexx::Hex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    PausableSystems,
+    gameplay::{camera::follow_camera, enemy::Enemy, layers, level::CurrentLevel, player::Player},
+    screens::Screen,
+};
+
+pub(crate) struct DecorationPlugin;
+impl Plugin for DecorationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::Gameplay), spawn_decorations)
+            .add_systems(
+                Update,
+                (
+                    drift_clouds,
+                    apply_parallax,
+                    (fade_decorations, hide_shapes_under_canopies),
+                )
+                    .chain()
+                    .after(follow_camera)
+                    .in_set(PausableSystems)
+                    .run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// Decoration of a level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct DecorationDef {
+    #[serde(default)]
+    pub canopies: Vec<CanopyDef>,
+    /// number of clouds drifting over the map
+    #[serde(default = "default_clouds")]
+    pub clouds: usize,
+    /// direction and speed the clouds drift in
+    #[serde(default = "default_wind")]
+    pub wind: [f32; 2],
+    /// shapes under a canopy can't be seen unless the player is under it too
+    #[serde(default)]
+    pub hide_shapes: bool,
+}
+
+/// Tree canopy over a hex
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct CanopyDef {
+    pub hex: [i32; 2],
+    /// radius in hexes
+    #[serde(default = "default_canopy_size")]
+    pub size: f32,
+}
+
+fn default_clouds() -> usize {
+    4
+}
+
+fn default_wind() -> [f32; 2] {
+    [12.0, 4.0]
+}
+
+fn default_canopy_size() -> f32 {
+    1.0
+}
+
+impl Default for DecorationDef {
+    fn default() -> Self {
+        Self {
+            canopies: Vec::new(),
+            clouds: default_clouds(),
+            wind: default_wind(),
+            hide_shapes: false,
+        }
+    }
+}
+
+/// Something drawn above the play area
+#[derive(Component)]
+struct Decoration {
+    /// position in the world when the camera is at the origin
+    anchor: Vec2,
+    /// how much faster than the map it moves with the camera, higher looks closer
+    parallax: f32,
+    radius: f32,
+    opacity: f32,
+}
+
+#[derive(Component)]
+struct Canopy;
+
+#[derive(Component)]
+struct Cloud {
+    velocity: Vec2,
+}
+
+/// Shape hidden under a canopy
+#[derive(Component)]
+pub(crate) struct UnderCanopy;
+
+/// Whether shapes under canopies are hidden in this level
+#[derive(Resource)]
+struct HideShapes;
+
+const CANOPY_PARALLAX: f32 = 0.05;
+const CLOUD_PARALLAX: f32 = 0.2;
+/// opacity while something is underneath
+const SEE_THROUGH: f32 = 0.3;
+const FADE_SPEED: f32 = 6.0;
+
+fn spawn_decorations(
+    mut commands: Commands,
+    level: CurrentLevel,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let map = &level.get().map;
+    let decoration = &map.decoration;
+    let layout = map.layout();
+
+    if decoration.hide_shapes {
+        commands.insert_resource(HideShapes);
+    } else {
+        commands.remove_resource::<HideShapes>();
+    }
+
+    for canopy in &decoration.canopies {
+        let radius = canopy.size * map.hex_size;
+        let anchor = layout.hex_to_world_pos(Hex::from(canopy.hex));
+        let color = Color::Srgba(tailwind::GREEN_900);
+        commands.spawn((
+            Name::new("Canopy"),
+            Canopy,
+            Decoration {
+                anchor,
+                parallax: CANOPY_PARALLAX,
+                radius,
+                opacity: 0.9,
+            },
+            Mesh2d(meshes.add(Circle::new(radius))),
+            // every decoration gets its own material so they can fade separately
+            MeshMaterial2d(materials.add(color.with_alpha(0.9))),
+            Transform::from_translation(anchor.extend(layers::CANOPY)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+
+    let bounds = map.bounds();
+    let wind = Vec2::from(decoration.wind);
+    let mut rng = rand::thread_rng();
+    for _ in 0..decoration.clouds {
+        let size = Vec2::new(rng.gen_range(2.0..4.0), rng.gen_range(1.0..2.0)) * map.hex_size;
+        let anchor = Vec2::new(
+            rng.gen_range(bounds.min.x..=bounds.max.x),
+            rng.gen_range(bounds.min.y..=bounds.max.y),
+        );
+        commands.spawn((
+            Name::new("Cloud"),
+            Cloud {
+                velocity: wind * rng.gen_range(0.7..1.3),
+            },
+            Decoration {
+                anchor,
+                parallax: CLOUD_PARALLAX,
+                radius: size.min_element(),
+                opacity: 0.6,
+            },
+            Mesh2d(meshes.add(Ellipse::new(size.x, size.y))),
+            MeshMaterial2d(materials.add(Color::WHITE.with_alpha(0.6))),
+            Transform::from_translation(anchor.extend(layers::CLOUDS)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+/// move clouds with the wind, wrapping around the map
+fn drift_clouds(
+    time: Res<Time>,
+    level: CurrentLevel,
+    mut clouds: Query<(&Cloud, &mut Decoration)>,
+) {
+    let map = &level.get().map;
+    // let clouds get fully off the map before they wrap
+    let bounds = map.bounds().inflate(map.hex_size * 4.0);
+    for (cloud, mut decoration) in &mut clouds {
+        let anchor = decoration.anchor + cloud.velocity * time.delta_secs();
+        decoration.anchor = (anchor - bounds.min).rem_euclid(bounds.size()) + bounds.min;
+    }
+}
+
+fn apply_parallax(
+    camera: Single<&Transform, (With<Camera2d>, Without<Decoration>)>,
+    mut decorations: Query<(&Decoration, &mut Transform)>,
+) {
+    let camera = camera.translation.truncate();
+    for (decoration, mut transform) in &mut decorations {
+        // higher things move against the camera, so they seem closer
+        let position = decoration.anchor - camera * decoration.parallax;
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+/// make decorations see-through while anything is under them
+fn fade_decorations(
+    time: Res<Time>,
+    hide_shapes: Option<Res<HideShapes>>,
+    player: Single<&Transform, With<Player>>,
+    shapes: Query<&Transform, With<Enemy>>,
+    decorations: Query<(
+        &Decoration,
+        &Transform,
+        &MeshMaterial2d<ColorMaterial>,
+        Has<Canopy>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let player = player.translation.truncate();
+    for (decoration, transform, material, canopy) in &decorations {
+        let center = transform.translation.truncate();
+        let under = |position: Vec2| position.distance(center) < decoration.radius;
+        // canopies that hide shapes only open up for the player
+        let covered = under(player)
+            || (!(canopy && hide_shapes.is_some())
+                && shapes.iter().any(|t| under(t.translation.truncate())));
+        let target = if covered {
+            SEE_THROUGH
+        } else {
+            decoration.opacity
+        };
+
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        let mut alpha = material.color.alpha();
+        if (alpha - target).abs() > 0.01 {
+            alpha.smooth_nudge(&target, FADE_SPEED, time.delta_secs());
+            material.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn hide_shapes_under_canopies(
+    mut commands: Commands,
+    hide_shapes: Option<Res<HideShapes>>,
+    player: Single<&Transform, With<Player>>,
+    canopies: Query<(&Decoration, &Transform), With<Canopy>>,
+    mut shapes: Query<(Entity, &Transform, &mut Visibility, Has<UnderCanopy>), With<Enemy>>,
+) {
+    let player = player.translation.truncate();
+    for (entity, transform, mut visibility, hidden) in &mut shapes {
+        let position = transform.translation.truncate();
+        let hide = hide_shapes.is_some()
+            && canopies.iter().any(|(decoration, canopy)| {
+                let center = canopy.translation.truncate();
+                position.distance(center) < decoration.radius
+                    && player.distance(center) >= decoration.radius
+            });
+        if hide && !hidden {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(UnderCanopy);
+        } else if !hide && hidden {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<UnderCanopy>();
+        }
+    }
+}
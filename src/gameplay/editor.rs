@@ -0,0 +1,532 @@
+//! Level editor for placing shapes, terrain and zones on the hex grid.
+
+use bevy::{color::palettes::tailwind, prelude::*, ui::Val::*};
+use hexx::Hex;
+
+use crate::{
+    gameplay::{
+        enemy::{EnemyHandles, EnemyType},
+        layers,
+        level::{ActiveLevel, Behaviour, CurrentLevel, Level, ShapeDef, ZoneDef, ZoneKind},
+        map::{HoveredHex, Map, MapHex},
+        terrain::Terrain,
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+pub(crate) struct EditorPlugin;
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorState>()
+            .init_resource::<EditorHandles>()
+            .add_systems(OnEnter(Screen::Editor), (start_editing, spawn_editor_ui))
+            .add_systems(
+                Update,
+                (
+                    pan_camera,
+                    select_tool,
+                    edit_level,
+                    spawn_markers.run_if(resource_changed::<EditorLevel>),
+                    draw_shape_gizmos,
+                    update_editor_ui.run_if(resource_changed::<EditorState>),
+                )
+                    .chain()
+                    .run_if(in_state(Screen::Editor)),
+            )
+            .add_systems(OnExit(Screen::Editor), reset_camera)
+            .add_systems(
+                OnEnter(Screen::Title),
+                end_test_play.run_if(resource_exists::<TestPlay>),
+            );
+    }
+}
+
+/// Where the editor saves levels, relative to the assets folder
+#[cfg(not(target_family = "wasm"))]
+const SAVE_PATH: &str = "levels/custom.level.ron";
+/// Fastest initial velocity that can be dragged out for a shape
+const MAX_VELOCITY: f32 = 300.0;
+const PAN_SPEED: f32 = 600.0;
+
+/// Shape types that can be placed, in the order they are cycled through
+const PLACEABLE: [EnemyType; 7] = [
+    EnemyType::Red,
+    EnemyType::Green,
+    EnemyType::Blue,
+    EnemyType::Purple,
+    EnemyType::Yellow,
+    EnemyType::Cyan,
+    EnemyType::White,
+];
+
+/// The level being edited. Kept while test playing so editing can continue afterwards.
+#[derive(Resource, Deref, DerefMut)]
+pub(crate) struct EditorLevel(Level);
+
+/// Present while a level from the editor is being played
+#[derive(Resource)]
+pub(crate) struct TestPlay;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Tool {
+    Shape,
+    Terrain,
+    SpawnZone,
+    Erase,
+}
+
+impl Tool {
+    fn name(&self) -> &'static str {
+        match self {
+            Tool::Shape => "Shape",
+            Tool::Terrain => "Terrain",
+            Tool::SpawnZone => "Spawn zone",
+            Tool::Erase => "Erase",
+        }
+    }
+}
+
+#[derive(Resource)]
+struct EditorState {
+    tool: Tool,
+    kind: EnemyType,
+    behaviour: Behaviour,
+    terrain: Terrain,
+    /// where the shape being placed was pressed, dragging sets its velocity
+    drag_start: Option<Vec2>,
+    status: String,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self {
+            tool: Tool::Shape,
+            kind: EnemyType::Red,
+            behaviour: Behaviour::Default,
+            terrain: Terrain::Wall,
+            drag_start: None,
+            status: String::new(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct EditorHandles {
+    zone_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for EditorHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        Self {
+            zone_material: materials.add(Color::Srgba(tailwind::AMBER_400.with_alpha(0.4))),
+        }
+    }
+}
+
+/// Visual for something placed in the level
+#[derive(Component)]
+struct EditorMarker;
+
+#[derive(Component)]
+struct EditorToolText;
+
+#[derive(Component)]
+struct EditorStatusText;
+
+fn start_editing(
+    mut commands: Commands,
+    editor_level: Option<ResMut<EditorLevel>>,
+    level: CurrentLevel,
+    mut state: ResMut<EditorState>,
+) {
+    match editor_level {
+        // markers were despawned when leaving to test play
+        Some(mut editor_level) => editor_level.set_changed(),
+        None => commands.insert_resource(EditorLevel(level.get().clone())),
+    }
+    commands.remove_resource::<TestPlay>();
+    state.drag_start = None;
+    state.status = String::new();
+}
+
+fn spawn_editor_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Editor Ui"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Px(10.0),
+                top: Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Px(4.0),
+                ..default()
+            },
+            StateScoped(Screen::Editor),
+            children![
+                (widget::label(""), EditorToolText),
+                (widget::label(""), EditorStatusText),
+                widget::label("1-4: tool  Q/E: shape  B: behaviour  T: terrain"),
+                widget::label("Drag to set velocity  Right click: erase"),
+                widget::label("WASD: pan  F5: test  Ctrl+S: save"),
+            ],
+        ))
+        .with_children(|parent| {
+            parent.spawn(widget::button_small("Test", test_play_button));
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button_small("Save", save_button));
+            parent.spawn(widget::button_small("Exit", exit_editor));
+        });
+}
+
+fn update_editor_ui(
+    state: Res<EditorState>,
+    mut tool_text: Single<&mut Text, (With<EditorToolText>, Without<EditorStatusText>)>,
+    mut status_text: Single<&mut Text, With<EditorStatusText>>,
+) {
+    tool_text.0 = match state.tool {
+        Tool::Shape => format!(
+            "Tool: {} ({:?}, {:?})",
+            state.tool.name(),
+            state.kind,
+            state.behaviour
+        ),
+        Tool::Terrain => format!("Tool: {} ({:?})", state.tool.name(), state.terrain),
+        tool => format!("Tool: {}", tool.name()),
+    };
+    status_text.0 = state.status.clone();
+}
+
+fn pan_camera(
+    key: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if key.pressed(KeyCode::KeyW) || key.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if key.pressed(KeyCode::KeyS) || key.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if key.pressed(KeyCode::KeyA) || key.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if key.pressed(KeyCode::KeyD) || key.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    camera.translation +=
+        (direction.normalize_or_zero() * PAN_SPEED * time.delta_secs()).extend(0.0);
+}
+
+fn reset_camera(mut camera: Single<&mut Transform, With<Camera2d>>) {
+    camera.translation = Vec3::ZERO;
+}
+
+fn select_tool(
+    mut commands: Commands,
+    key: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+) {
+    for (code, tool) in [
+        (KeyCode::Digit1, Tool::Shape),
+        (KeyCode::Digit2, Tool::Terrain),
+        (KeyCode::Digit3, Tool::SpawnZone),
+        (KeyCode::Digit4, Tool::Erase),
+    ] {
+        if key.just_pressed(code) {
+            state.tool = tool;
+            state.drag_start = None;
+        }
+    }
+
+    let index = PLACEABLE
+        .iter()
+        .position(|t| *t == state.kind)
+        .unwrap_or_default();
+    if key.just_pressed(KeyCode::KeyE) {
+        state.kind = PLACEABLE[(index + 1) % PLACEABLE.len()];
+    }
+    if key.just_pressed(KeyCode::KeyQ) {
+        state.kind = PLACEABLE[(index + PLACEABLE.len() - 1) % PLACEABLE.len()];
+    }
+    if key.just_pressed(KeyCode::KeyB) {
+        state.behaviour = state.behaviour.next();
+    }
+    if key.just_pressed(KeyCode::KeyT) {
+        state.terrain = state.terrain.next();
+    }
+
+    if key.just_pressed(KeyCode::F5) {
+        commands.run_system_cached(test_play);
+    }
+    let ctrl = key.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && key.just_pressed(KeyCode::KeyS) {
+        state.status = save(&mut level);
+    }
+}
+
+fn edit_level(
+    mouse: Res<ButtonInput<MouseButton>>,
+    hovered: Res<HoveredHex>,
+    interactions: Query<&Interaction>,
+    mut state: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+) {
+    // don't edit the level through the buttons
+    if interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    if state.tool == Tool::Shape
+        && mouse.just_released(MouseButton::Left)
+        && let Some(start) = state.drag_start.take()
+    {
+        let end = hovered.position.unwrap_or(start);
+        level.shapes.push(ShapeDef {
+            kind: state.kind,
+            position: start.to_array(),
+            velocity: (end - start).clamp_length_max(MAX_VELOCITY).to_array(),
+            behaviour: state.behaviour,
+        });
+    }
+
+    let Some(hex) = hovered.hex else {
+        return;
+    };
+    let position = hovered.position.unwrap_or_default();
+
+    if mouse.pressed(MouseButton::Right) {
+        erase(&mut level, hex);
+        return;
+    }
+
+    match state.tool {
+        Tool::Shape => {
+            if mouse.just_pressed(MouseButton::Left) && !level.map.is_wall(hex) {
+                state.drag_start = Some(position);
+            }
+        }
+        Tool::Terrain => {
+            if mouse.pressed(MouseButton::Left) && level.map.terrain(hex) != state.terrain {
+                if state.terrain == Terrain::Wall {
+                    erase(&mut level, hex);
+                }
+                level.map.set_terrain(hex, state.terrain);
+            }
+        }
+        Tool::SpawnZone => {
+            if mouse.pressed(MouseButton::Left)
+                && !level.map.is_wall(hex)
+                && level.map.zone(hex).is_none()
+            {
+                level.map.zones.push(ZoneDef {
+                    hex: hex.to_array(),
+                    kind: ZoneKind::Spawn,
+                });
+            }
+        }
+        Tool::Erase => {
+            if mouse.pressed(MouseButton::Left) {
+                erase(&mut level, hex);
+            }
+        }
+    }
+}
+
+/// remove everything placed on `hex`
+fn erase(level: &mut ResMut<EditorLevel>, hex: Hex) {
+    let layout = level.map.layout();
+    let has_shape = level
+        .shapes
+        .iter()
+        .any(|shape| layout.world_pos_to_hex(Vec2::from(shape.position)) == hex);
+    let has_tile = level.map.terrain(hex) != level.map.terrain || level.map.zone(hex).is_some();
+    // avoid triggering change detection when there is nothing to erase
+    if !has_shape && !has_tile {
+        return;
+    }
+
+    level
+        .shapes
+        .retain(|shape| layout.world_pos_to_hex(Vec2::from(shape.position)) != hex);
+    let terrain = level.map.terrain;
+    level.map.set_terrain(hex, terrain);
+    level.map.zones.retain(|zone| zone.hex != hex.to_array());
+}
+
+fn spawn_markers(
+    mut commands: Commands,
+    level: Res<EditorLevel>,
+    map: Res<Map>,
+    enemy_handles: Res<EnemyHandles>,
+    editor_handles: Res<EditorHandles>,
+    markers: Query<Entity, With<EditorMarker>>,
+    mut hexes: Query<(&MapHex, &mut MeshMaterial2d<ColorMaterial>)>,
+) {
+    for marker in &markers {
+        commands.entity(marker).despawn();
+    }
+
+    for (MapHex(hex), mut material) in &mut hexes {
+        let terrain_material = map.material(level.map.terrain(*hex));
+        if material.0 != terrain_material {
+            material.0 = terrain_material;
+        }
+    }
+
+    let hex_marker = |hex: Hex, material: Handle<ColorMaterial>| {
+        (
+            Name::new("Editor Hex"),
+            EditorMarker,
+            StateScoped(Screen::Editor),
+            Mesh2d(map.mesh_handle.clone()),
+            MeshMaterial2d(material),
+            Transform::from_translation(map.layout.hex_to_world_pos(hex).extend(layers::MAP + 0.1)),
+        )
+    };
+    for zone in &level.map.zones {
+        let material = match zone.kind {
+            ZoneKind::Spawn => editor_handles.zone_material.clone(),
+        };
+        commands.spawn(hex_marker(Hex::from(zone.hex), material));
+    }
+    for shape in &level.shapes {
+        commands.spawn((
+            Name::new("Editor Shape"),
+            EditorMarker,
+            StateScoped(Screen::Editor),
+            Mesh2d(enemy_handles.mesh(shape.kind)),
+            MeshMaterial2d(enemy_handles.material(shape.kind)),
+            Transform::from_translation(Vec2::from(shape.position).extend(layers::ON_GROUND)),
+        ));
+    }
+}
+
+/// velocity arrows and the range shapes react to the player in
+fn draw_shape_gizmos(
+    mut gizmos: Gizmos,
+    level: Res<EditorLevel>,
+    state: Res<EditorState>,
+    hovered: Res<HoveredHex>,
+) {
+    for shape in &level.shapes {
+        let position = Vec2::from(shape.position);
+        let velocity = Vec2::from(shape.velocity);
+        if velocity != Vec2::ZERO {
+            gizmos.arrow_2d(position, position + velocity, Color::WHITE);
+        }
+        match shape.behaviour {
+            Behaviour::Chase => {
+                gizmos.circle_2d(position, 150.0, tailwind::RED_400);
+            }
+            Behaviour::Flee => {
+                gizmos.circle_2d(position, 150.0, tailwind::SKY_400);
+            }
+            Behaviour::Default | Behaviour::Drift => {}
+        }
+    }
+
+    if let (Some(start), Some(end)) = (state.drag_start, hovered.position) {
+        let end = start + (end - start).clamp_length_max(MAX_VELOCITY);
+        gizmos.arrow_2d(start, end, state.kind.color());
+    }
+}
+
+fn test_play(
+    mut commands: Commands,
+    level: Res<EditorLevel>,
+    mut levels: ResMut<Assets<Level>>,
+    mut active: ResMut<ActiveLevel>,
+    mut state: ResMut<EditorState>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if let Err(err) = level.validate() {
+        state.status = err.to_string();
+        return;
+    }
+
+    active.handle = levels.add(level.0.clone());
+    commands.insert_resource(TestPlay);
+    next_screen.set(Screen::Gameplay);
+}
+
+fn test_play_button(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached(test_play);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn save_button(
+    _: Trigger<Pointer<Click>>,
+    mut level: ResMut<EditorLevel>,
+    mut state: ResMut<EditorState>,
+) {
+    state.status = save(&mut level);
+}
+
+/// Write the level to [`SAVE_PATH`] and describe how it went
+#[cfg(not(target_family = "wasm"))]
+fn save(level: &mut EditorLevel) -> String {
+    use bevy::asset::io::file::FileAssetReader;
+
+    if let Err(err) = level.validate() {
+        return err.to_string();
+    }
+    if level.name == Level::default().name {
+        level.name = "Custom".to_string();
+    }
+
+    let contents = match ron::ser::to_string_pretty(&level.0, default()) {
+        Ok(contents) => contents,
+        Err(err) => return format!("Could not serialize level: {err}"),
+    };
+    let path = FileAssetReader::get_base_path()
+        .join("assets")
+        .join(SAVE_PATH);
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, contents));
+    match result {
+        Ok(()) => format!("Saved to {SAVE_PATH}"),
+        Err(err) => format!("Could not save level: {err}"),
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn save(_level: &mut EditorLevel) -> String {
+    "Levels can't be saved in the browser".to_string()
+}
+
+fn exit_editor(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut active: ResMut<ActiveLevel>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    commands.remove_resource::<EditorLevel>();
+    active.reset(&assets);
+    next_screen.set(Screen::Title);
+}
+
+/// leaving a test play for the title screen ends the editing session
+fn end_test_play(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut active: ResMut<ActiveLevel>,
+) {
+    commands.remove_resource::<TestPlay>();
+    commands.remove_resource::<EditorLevel>();
+    active.reset(&assets);
+}
+
+/// Button on the score screen for going back to editing after a test play
+pub(crate) fn back_to_editor(
+    _: Trigger<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Editor);
+}
@@ -1,17 +1,19 @@
 use std::f32::consts::PI;
 
 use avian2d::prelude::{
-    AngularVelocity, CoefficientCombine, Collider, Friction, LinearVelocity, MaxLinearSpeed,
-    Restitution, RigidBody,
+    AngularVelocity, CoefficientCombine, Collider, ExternalForce, Friction, LinearDamping,
+    LinearVelocity, MaxLinearSpeed, Restitution, RigidBody,
 };
 use bevy::color::palettes::tailwind;
 use bevy::math::ops::cos;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::gameplay::DespawnSet;
 use crate::gameplay::enemy_ai::FollowPlayer;
+use crate::gameplay::level::CurrentLevel;
 use crate::gameplay::rng_bag::RngBag;
 use crate::screens::Screen;
 
@@ -19,13 +21,10 @@ pub(crate) struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EnemyHandles>()
+            .init_resource::<ShapeCounts>()
             .add_event::<SpawnEnemies>()
-            .add_systems(
-                OnEnter(Screen::Gameplay),
-                |mut spawn: EventWriter<SpawnEnemies>| {
-                    spawn.write(SpawnEnemies);
-                },
-            )
+            .add_observer(count_added_shape)
+            .add_observer(count_removed_shape)
             .add_systems(Update, spawn_enemies.run_if(in_state(Screen::Gameplay)))
             .add_systems(
                 OnExit(Screen::Gameplay),
@@ -38,8 +37,48 @@ impl Plugin for EnemyPlugin {
 #[derive(Component)]
 pub struct Enemy;
 
-#[derive(Event)]
-pub struct SpawnEnemies;
+/// Spawn random primaries inside the level's spawn area
+#[derive(Event, Default)]
+pub struct SpawnEnemies {
+    /// number of shapes, picked from the number already alive when `None`
+    pub count: Option<usize>,
+}
+
+/// Number of living shapes of each type
+#[derive(Resource, Default)]
+pub(crate) struct ShapeCounts(HashMap<EnemyType, usize>);
+
+impl ShapeCounts {
+    pub fn get(&self, t: EnemyType) -> usize {
+        self.0.get(&t).copied().unwrap_or_default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+}
+
+fn count_added_shape(
+    trigger: Trigger<OnAdd, EnemyType>,
+    enemies: Query<&EnemyType>,
+    mut counts: ResMut<ShapeCounts>,
+) {
+    if let Ok(t) = enemies.get(trigger.target()) {
+        *counts.0.entry(*t).or_default() += 1;
+    }
+}
+
+fn count_removed_shape(
+    trigger: Trigger<OnRemove, EnemyType>,
+    enemies: Query<&EnemyType>,
+    mut counts: ResMut<ShapeCounts>,
+) {
+    if let Ok(t) = enemies.get(trigger.target())
+        && let Some(count) = counts.0.get_mut(t)
+    {
+        *count = count.saturating_sub(1);
+    }
+}
 
 #[derive(Resource)]
 pub(crate) struct EnemyHandles {
@@ -99,7 +138,7 @@ impl FromWorld for EnemyHandles {
 }
 
 impl EnemyHandles {
-    fn mesh(&self, t: EnemyType) -> Handle<Mesh> {
+    pub fn mesh(&self, t: EnemyType) -> Handle<Mesh> {
         match t {
             EnemyType::Red => self.red_mesh.clone(),
             EnemyType::Green => self.green_mesh.clone(),
@@ -112,7 +151,7 @@ impl EnemyHandles {
         }
     }
 
-    fn material(&self, t: EnemyType) -> Handle<ColorMaterial> {
+    pub fn material(&self, t: EnemyType) -> Handle<ColorMaterial> {
         match t {
             EnemyType::Red => self.red_material.clone(),
             EnemyType::Green => self.green_material.clone(),
@@ -126,7 +165,7 @@ impl EnemyHandles {
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Debug)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum EnemyType {
     Red,
     Green,
@@ -146,8 +185,7 @@ impl EnemyType {
         transform: Transform,
         velocity: LinearVelocity,
         handles: &EnemyHandles,
-    ) {
-        let collider_size = if self == EnemyType::White { 15. } else { 10. };
+    ) -> Entity {
         let mesh = handles.mesh(self);
         let material = handles.material(self);
         let id = commands
@@ -161,9 +199,13 @@ impl EnemyType {
                 velocity,
                 AngularVelocity::default(),
                 Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
-                Collider::circle(collider_size),
+                self.collider(),
                 Restitution::new(0.8),
                 MaxLinearSpeed(100.0),
+                // set by the terrain the shape is on
+                LinearDamping::default(),
+                // added to every step by ai and currents
+                ExternalForce::ZERO.with_persistence(false),
             ))
             .id();
 
@@ -183,9 +225,18 @@ impl EnemyType {
             }
             _ => {}
         }
+
+        id
     }
 
     fn material(&self) -> ColorMaterial {
+        ColorMaterial {
+            color: self.color(),
+            ..default()
+        }
+    }
+
+    pub fn color(&self) -> Color {
         let color = match self {
             EnemyType::Red => tailwind::RED_500,
             EnemyType::Green => tailwind::GREEN_500,
@@ -197,38 +248,49 @@ impl EnemyType {
             EnemyType::None => unreachable!(),
         };
 
-        ColorMaterial {
-            color: color.into(),
-            ..default()
-        }
+        color.into()
     }
 
     fn mesh(&self) -> Mesh {
+        self.shape().mesh()
+    }
+
+    fn collider(&self) -> Collider {
+        self.shape().collider()
+    }
+
+    /// unscaled area of the shape
+    pub fn area(&self) -> f32 {
+        self.shape().area()
+    }
+
+    /// Primitive shared by the mesh and the collider so they always line up
+    fn shape(&self) -> EnemyShape {
         const SHAPE_LENGTH: f32 = 20.;
         match self {
             EnemyType::Red => {
                 let triangle_height = SHAPE_LENGTH * cos(PI / 3.);
-                Triangle2d::new(
+                EnemyShape::Triangle(Triangle2d::new(
                     Vec2::new(0., triangle_height),
                     Vec2::new(-SHAPE_LENGTH / 2., -triangle_height),
                     Vec2::new(SHAPE_LENGTH / 2., -triangle_height),
-                )
-                .into()
+                ))
+            }
+            EnemyType::Green => EnemyShape::Polygon(RegularPolygon::new(0.55 * SHAPE_LENGTH, 6)),
+            EnemyType::Blue => EnemyShape::Rectangle(Rectangle::new(SHAPE_LENGTH, SHAPE_LENGTH)),
+            EnemyType::Purple => EnemyShape::Polygon(RegularPolygon::new(1.1 * SHAPE_LENGTH, 6)),
+            EnemyType::Yellow => {
+                EnemyShape::Rectangle(Rectangle::new(2. * SHAPE_LENGTH, 2. * SHAPE_LENGTH))
             }
-            EnemyType::Green => RegularPolygon::new(0.55 * SHAPE_LENGTH, 6).into(),
-            EnemyType::Blue => Rectangle::new(SHAPE_LENGTH, SHAPE_LENGTH).into(),
-            EnemyType::Purple => RegularPolygon::new(1.1 * SHAPE_LENGTH, 6).into(),
-            EnemyType::Yellow => Rectangle::new(2. * SHAPE_LENGTH, 2. * SHAPE_LENGTH).into(),
             EnemyType::Cyan => {
                 let triangle_height = 2. * SHAPE_LENGTH * cos(PI / 3.);
-                Triangle2d::new(
+                EnemyShape::Triangle(Triangle2d::new(
                     Vec2::new(0., triangle_height),
                     Vec2::new(-SHAPE_LENGTH, -triangle_height),
                     Vec2::new(SHAPE_LENGTH, -triangle_height),
-                )
-                .into()
+                ))
             }
-            EnemyType::White => Circle::new(0.75 * SHAPE_LENGTH).into(),
+            EnemyType::White => EnemyShape::Circle(Circle::new(0.75 * SHAPE_LENGTH)),
             EnemyType::None => unreachable!(),
         }
     }
@@ -262,28 +324,70 @@ impl EnemyType {
     }
 }
 
+/// Primitive an enemy is drawn and collides with
+enum EnemyShape {
+    Triangle(Triangle2d),
+    Polygon(RegularPolygon),
+    Rectangle(Rectangle),
+    Circle(Circle),
+}
+
+impl EnemyShape {
+    fn mesh(&self) -> Mesh {
+        match *self {
+            EnemyShape::Triangle(triangle) => triangle.into(),
+            EnemyShape::Polygon(polygon) => polygon.into(),
+            EnemyShape::Rectangle(rectangle) => rectangle.into(),
+            EnemyShape::Circle(circle) => circle.into(),
+        }
+    }
+
+    fn collider(&self) -> Collider {
+        match *self {
+            EnemyShape::Triangle(Triangle2d {
+                vertices: [a, b, c],
+            }) => Collider::triangle(a, b, c),
+            EnemyShape::Polygon(polygon) => {
+                Collider::convex_hull(polygon.vertices(0.0).into_iter().collect())
+                    .unwrap_or_else(|| Collider::circle(polygon.circumradius()))
+            }
+            EnemyShape::Rectangle(rectangle) => {
+                Collider::rectangle(2. * rectangle.half_size.x, 2. * rectangle.half_size.y)
+            }
+            EnemyShape::Circle(circle) => Collider::circle(circle.radius),
+        }
+    }
+
+    fn area(&self) -> f32 {
+        match self {
+            EnemyShape::Triangle(triangle) => triangle.area(),
+            EnemyShape::Polygon(polygon) => polygon.area(),
+            EnemyShape::Rectangle(rectangle) => rectangle.area(),
+            EnemyShape::Circle(circle) => circle.area(),
+        }
+    }
+}
+
 fn spawn_enemies(
     mut commands: Commands,
     handles: Res<EnemyHandles>,
     mut spawn: EventReader<SpawnEnemies>,
     enemies: Query<(), With<Enemy>>,
-    window: Single<&Window, With<PrimaryWindow>>,
+    level: CurrentLevel,
 ) {
     let mut rng_bag = RngBag::new(vec![EnemyType::Red, EnemyType::Blue, EnemyType::Green]);
     let mut rng = rand::thread_rng();
-    for _ in spawn.read() {
-        let item_count = if enemies.iter().len() < 3 { 3 } else { 6 };
+    let map = &level.get().map;
+    for event in spawn.read() {
+        let item_count = event
+            .count
+            .unwrap_or(if enemies.iter().len() < 3 { 3 } else { 6 });
         for _ in 0..item_count {
             let typ = rng_bag.get();
             const MAX_VELOCITY: f32 = 100.0;
-            let max_t = window.size() / 2. - 20.;
             typ.spawn(
                 &mut commands,
-                Transform::from_xyz(
-                    rng.gen_range(-max_t.x..max_t.x),
-                    rng.gen_range(-max_t.y..max_t.y),
-                    0.,
-                ),
+                Transform::from_translation(map.random_spawn_position(&mut rng).extend(0.)),
                 LinearVelocity(Vec2::new(
                     rng.gen_range(-MAX_VELOCITY..MAX_VELOCITY),
                     rng.gen_range(-MAX_VELOCITY..MAX_VELOCITY),
@@ -1,3 +1,4 @@
+use crate::gameplay::pathfinding::{NavGrid, Route};
 use crate::gameplay::player::Player;
 use avian2d::prelude::ExternalForce;
 use bevy::prelude::*;
@@ -11,7 +12,7 @@ impl Plugin for EnemyAiPlugin {
 }
 
 #[derive(Component)]
-#[require(ExternalForce)]
+#[require(ExternalForce = ExternalForce::ZERO.with_persistence(false), Route)]
 pub struct FollowPlayer {
     /// scalar acceleration to apply in direction of player
     /// use negative acceleration to move away from player
@@ -20,16 +21,25 @@ pub struct FollowPlayer {
     pub distance: f32,
 }
 
-fn follow_player(
+pub(crate) fn follow_player(
+    nav: Res<NavGrid>,
     player: Single<&Transform, With<Player>>,
-    mut followers: Query<(&mut ExternalForce, &Transform, &FollowPlayer)>,
+    mut followers: Query<(&mut ExternalForce, &mut Route, &Transform, &FollowPlayer)>,
 ) {
-    for (mut f, t, follow) in &mut followers {
-        let player_direction = (player.translation - t.translation).truncate();
-        if player_direction.length() < follow.distance {
-            **f = follow.acceleration * player_direction;
-        } else {
-            **f = Vec2::ZERO;
+    let target = player.translation.truncate();
+    for (mut f, mut route, t, follow) in &mut followers {
+        let position = t.translation.truncate();
+        let distance = position.distance(target);
+        // forces are cleared every physics step so other systems can add their own
+        if distance >= follow.distance {
+            continue;
         }
+        // go around walls instead of pushing into them
+        let direction = if follow.acceleration >= 0.0 {
+            (route.waypoint(&nav, position, target) - position).normalize_or_zero()
+        } else {
+            nav.away_from(position, target)
+        };
+        f.apply_force(follow.acceleration.abs() * distance * direction);
     }
 }
@@ -0,0 +1,203 @@
+//! Currents on the hex map that carry shapes along.
+
+use avian2d::prelude::{ComputedMass, ExternalForce};
+use bevy::{
+    color::palettes::tailwind, input::common_conditions::input_just_pressed,
+    platform::collections::HashMap, prelude::*,
+};
+use hexx::{Hex, HexLayout};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        enemy::Enemy,
+        enemy_ai::follow_player,
+        level::{CurrentLevel, MapDef},
+        physics::Velocity,
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct FlowPlugin;
+impl Plugin for FlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlowField>()
+            .init_resource::<FlowOverlay>()
+            .add_systems(OnEnter(Screen::Gameplay), build_flow_field)
+            .add_systems(
+                FixedUpdate,
+                (
+                    push_shapes.after(follow_player),
+                    push_player.run_if(|field: Res<FlowField>| field.push_player),
+                )
+                    .run_if(in_state(Screen::Gameplay)),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay.run_if(input_just_pressed(KeyCode::F3)),
+                    draw_overlay.run_if(|overlay: Res<FlowOverlay>| overlay.0),
+                )
+                    .run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// Currents of a level
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct FlowDef {
+    /// generated currents, `currents` are added on top
+    #[serde(default)]
+    pub pattern: Option<FlowPattern>,
+    #[serde(default)]
+    pub currents: Vec<CurrentDef>,
+    /// whether currents also push the player
+    #[serde(default)]
+    pub push_player: bool,
+}
+
+/// Current in a single hex
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct CurrentDef {
+    pub hex: [i32; 2],
+    /// acceleration applied to anything in the hex
+    pub force: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) enum FlowPattern {
+    /// the same current everywhere
+    Stream { force: [f32; 2] },
+    /// circle around the center, counter clockwise for positive strength
+    Vortex { strength: f32 },
+    /// smoothly varying random currents
+    Random { seed: u64, strength: f32 },
+}
+
+impl FlowDef {
+    /// current in every hex of the map that has one
+    pub fn generate(&self, map: &MapDef) -> HashMap<Hex, Vec2> {
+        let layout = map.layout();
+        let mut field: HashMap<Hex, Vec2> = match &self.pattern {
+            None => HashMap::default(),
+            Some(FlowPattern::Stream { force }) => {
+                map.hexes().map(|hex| (hex, Vec2::from(*force))).collect()
+            }
+            Some(FlowPattern::Vortex { strength }) => map
+                .hexes()
+                .map(|hex| {
+                    let tangent = layout.hex_to_world_pos(hex).normalize_or_zero().perp();
+                    (hex, tangent * *strength)
+                })
+                .collect(),
+            Some(FlowPattern::Random { seed, strength }) => {
+                let mut rng = StdRng::seed_from_u64(*seed);
+                let random: HashMap<Hex, Vec2> = map
+                    .hexes()
+                    .map(|hex| {
+                        (
+                            hex,
+                            Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)),
+                        )
+                    })
+                    .collect();
+                // average with the neighbours so currents line up
+                random
+                    .iter()
+                    .map(|(hex, direction)| {
+                        let sum = hex
+                            .all_neighbors()
+                            .iter()
+                            .filter_map(|n| random.get(n))
+                            .fold(*direction, |sum, d| sum + *d);
+                        (*hex, sum.normalize_or_zero() * *strength)
+                    })
+                    .collect()
+            }
+        };
+
+        for current in &self.currents {
+            *field.entry(Hex::from(current.hex)).or_default() += Vec2::from(current.force);
+        }
+        field.retain(|hex, force| !map.is_wall(*hex) && *force != Vec2::ZERO);
+        field
+    }
+}
+
+/// Currents of the level being played
+#[derive(Resource, Default)]
+pub(crate) struct FlowField {
+    layout: HexLayout,
+    hex_size: f32,
+    currents: HashMap<Hex, Vec2>,
+    push_player: bool,
+}
+
+impl FlowField {
+    pub fn at(&self, position: Vec2) -> Vec2 {
+        let hex = self.layout.world_pos_to_hex(position);
+        self.currents.get(&hex).copied().unwrap_or_default()
+    }
+}
+
+/// Whether to draw the currents
+#[derive(Resource, Default)]
+struct FlowOverlay(bool);
+
+fn build_flow_field(mut commands: Commands, level: CurrentLevel) {
+    let map = &level.get().map;
+    commands.insert_resource(FlowField {
+        layout: map.layout(),
+        hex_size: map.hex_size,
+        currents: map.flow.generate(map),
+        push_player: map.flow.push_player,
+    });
+}
+
+fn push_shapes(
+    field: Res<FlowField>,
+    mut shapes: Query<(&Transform, &ComputedMass, &mut ExternalForce), With<Enemy>>,
+) {
+    if field.currents.is_empty() {
+        return;
+    }
+    for (transform, mass, mut force) in &mut shapes {
+        let current = field.at(transform.translation.truncate());
+        if current != Vec2::ZERO {
+            force.apply_force(current * mass.value());
+        }
+    }
+}
+
+fn push_player(
+    time: Res<Time>,
+    field: Res<FlowField>,
+    player: Single<(&Transform, &mut Velocity), With<Player>>,
+) {
+    let (transform, mut velocity) = player.into_inner();
+    velocity.0 += field.at(transform.translation.truncate()) * time.delta_secs();
+}
+
+fn toggle_overlay(mut overlay: ResMut<FlowOverlay>) {
+    overlay.0 = !overlay.0;
+}
+
+fn draw_overlay(mut gizmos: Gizmos, field: Res<FlowField>) {
+    // longest arrow is most of a hex
+    let max = field
+        .currents
+        .values()
+        .map(|c| c.length())
+        .fold(0.0, f32::max);
+    if max == 0.0 {
+        return;
+    }
+    let scale = field.hex_size * 1.5 / max;
+    for (hex, current) in &field.currents {
+        let center = field.layout.hex_to_world_pos(*hex);
+        let half = *current * scale / 2.0;
+        gizmos.arrow_2d(center - half, center + half, tailwind::SKY_300);
+    }
+}
@@ -0,0 +1,187 @@
+//! Pens on the map that shapes can be herded into.
+
+use avian2d::prelude::{ComputedMass, ExternalForce, LinearVelocity, RigidBody};
+use bevy::{platform::collections::HashMap, prelude::*};
+use hexx::{Hex, HexLayout};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        enemy::{Enemy, EnemyType},
+        enemy_ai::FollowPlayer,
+        layers,
+        level::CurrentLevel,
+        map::Map,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct GoalPlugin;
+impl Plugin for GoalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GoalPens>()
+            .init_resource::<HerdedShapes>()
+            .add_systems(
+                OnEnter(Screen::Gameplay),
+                (build_goal_pens, spawn_goal_tiles).chain(),
+            )
+            .add_systems(FixedUpdate, herd_shapes.run_if(in_state(Screen::Gameplay)))
+            .add_systems(
+                Update,
+                count_herded_shapes.run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// Group of hexes that takes shapes of one colour
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct GoalPenDef {
+    pub hexes: Vec<[i32; 2]>,
+    pub accepts: EnemyType,
+    /// shapes that get in can't leave
+    #[serde(default)]
+    pub lock: bool,
+}
+
+/// Goal pens of the level being played
+#[derive(Resource, Default)]
+struct GoalPens {
+    layout: HexLayout,
+    /// index into `pens` for every hex in a pen
+    hexes: HashMap<Hex, usize>,
+    pens: Vec<GoalPen>,
+}
+
+struct GoalPen {
+    accepts: EnemyType,
+    lock: bool,
+    center: Vec2,
+}
+
+impl GoalPens {
+    fn at(&self, position: Vec2) -> Option<&GoalPen> {
+        let hex = self.layout.world_pos_to_hex(position);
+        self.hexes.get(&hex).map(|i| &self.pens[*i])
+    }
+}
+
+/// Shape sitting in a pen that accepts it
+#[derive(Component)]
+struct Penned;
+
+/// Number of shapes of each type in pens that accept them
+#[derive(Resource, Default, PartialEq)]
+pub(crate) struct HerdedShapes(HashMap<EnemyType, usize>);
+
+impl HerdedShapes {
+    pub fn get(&self, t: EnemyType) -> usize {
+        self.0.get(&t).copied().unwrap_or_default()
+    }
+}
+
+/// acceleration pushing shapes of the wrong colour out of a pen
+const REJECT_ACCELERATION: f32 = 400.0;
+
+fn build_goal_pens(mut commands: Commands, level: CurrentLevel) {
+    let map = &level.get().map;
+    let layout = map.layout();
+    let mut hexes = HashMap::default();
+    let pens = map
+        .goals
+        .iter()
+        .enumerate()
+        .map(|(i, def)| {
+            let mut center = Vec2::ZERO;
+            for hex in &def.hexes {
+                hexes.insert(Hex::from(*hex), i);
+                center += layout.hex_to_world_pos(Hex::from(*hex));
+            }
+            GoalPen {
+                accepts: def.accepts,
+                lock: def.lock,
+                center: center / def.hexes.len().max(1) as f32,
+            }
+        })
+        .collect();
+    commands.insert_resource(GoalPens {
+        layout,
+        hexes,
+        pens,
+    });
+}
+
+fn spawn_goal_tiles(
+    mut commands: Commands,
+    map: Res<Map>,
+    pens: Res<GoalPens>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let materials = pens
+        .pens
+        .iter()
+        .map(|pen| materials.add(pen.accepts.color().with_alpha(0.3)))
+        .collect::<Vec<_>>();
+    for (hex, i) in &pens.hexes {
+        let position = map.layout.hex_to_world_pos(*hex);
+        commands.spawn((
+            Name::new("Goal Pen Hex"),
+            Mesh2d(map.mesh_handle.clone()),
+            MeshMaterial2d(materials[*i].clone()),
+            Transform::from_translation(position.extend(layers::MAP + 0.15)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn herd_shapes(
+    mut commands: Commands,
+    pens: Res<GoalPens>,
+    mut shapes: Query<
+        (
+            Entity,
+            &Transform,
+            &EnemyType,
+            &ComputedMass,
+            &mut ExternalForce,
+            &mut LinearVelocity,
+            Has<Penned>,
+        ),
+        With<Enemy>,
+    >,
+) {
+    if pens.pens.is_empty() {
+        return;
+    }
+    for (entity, transform, enemy_type, mass, mut force, mut velocity, penned) in &mut shapes {
+        let position = transform.translation.truncate();
+        match pens.at(position) {
+            Some(pen) if pen.accepts == *enemy_type => {
+                if penned {
+                    continue;
+                }
+                let mut entity = commands.entity(entity);
+                entity.insert(Penned);
+                if pen.lock {
+                    velocity.0 = Vec2::ZERO;
+                    entity.remove::<FollowPlayer>().insert(RigidBody::Static);
+                }
+            }
+            Some(pen) => {
+                let out = (position - pen.center).normalize_or(Vec2::X);
+                force.apply_force(out * REJECT_ACCELERATION * mass.value());
+            }
+            None if penned => {
+                commands.entity(entity).remove::<Penned>();
+            }
+            None => {}
+        }
+    }
+}
+
+fn count_herded_shapes(shapes: Query<&EnemyType, With<Penned>>, mut herded: ResMut<HerdedShapes>) {
+    let mut counts = HerdedShapes::default();
+    for enemy_type in &shapes {
+        *counts.0.entry(*enemy_type).or_default() += 1;
+    }
+    herded.set_if_neq(counts);
+}
@@ -0,0 +1,183 @@
+//! Gravity wells, repulsors and destroy zones placed by the level.
+
+use avian2d::prelude::{ColliderDisabled, ComputedMass, ExternalForce};
+use bevy::{color::palettes::tailwind, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        enemy::Enemy, layers, level::CurrentLevel, path::PathSet, physics::Velocity,
+        player::Player, state::Playing,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct HazardPlugin;
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::Gameplay), spawn_hazards)
+            .add_systems(
+                FixedUpdate,
+                (pull_shapes, pull_player, destroy_shapes, destroy_player)
+                    // shapes in a loop get their colliders disabled when combining starts
+                    .after(PathSet)
+                    .run_if(in_state(Screen::Gameplay))
+                    .run_if(in_state(Playing::Live)),
+            );
+    }
+}
+
+/// Hazard placed in a level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct HazardDef {
+    pub kind: HazardKind,
+    pub position: [f32; 2],
+    pub radius: f32,
+    /// whether the hazard also pulls, pushes or kills the player
+    #[serde(default)]
+    pub player: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum HazardKind {
+    /// pulls things towards the center, strongest in the middle
+    Well { strength: f32 },
+    /// pushes things away from the center, strongest in the middle
+    Repulsor { strength: f32 },
+    /// removes shapes that get inside
+    Destroy,
+}
+
+impl HazardKind {
+    fn color(&self) -> Color {
+        Color::Srgba(match self {
+            HazardKind::Well { .. } => tailwind::INDIGO_500,
+            HazardKind::Repulsor { .. } => tailwind::ORANGE_400,
+            HazardKind::Destroy => tailwind::RED_700,
+        })
+    }
+
+    /// acceleration towards the center, negative pushes away
+    fn pull(&self) -> f32 {
+        match self {
+            HazardKind::Well { strength } => *strength,
+            HazardKind::Repulsor { strength } => -*strength,
+            HazardKind::Destroy => 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct Hazard {
+    kind: HazardKind,
+    radius: f32,
+    player: bool,
+}
+
+impl Hazard {
+    /// acceleration on something at `position`, zero outside the radius
+    fn acceleration(&self, center: Vec2, position: Vec2) -> Vec2 {
+        let offset = center - position;
+        let distance = offset.length();
+        if distance >= self.radius {
+            return Vec2::ZERO;
+        }
+        let falloff = 1.0 - distance / self.radius;
+        offset.normalize_or_zero() * self.kind.pull() * falloff
+    }
+
+    fn contains(&self, center: Vec2, position: Vec2) -> bool {
+        center.distance(position) < self.radius
+    }
+}
+
+fn spawn_hazards(
+    mut commands: Commands,
+    level: CurrentLevel,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for def in &level.get().map.hazards {
+        commands.spawn((
+            Name::new("Hazard"),
+            Hazard {
+                kind: def.kind,
+                radius: def.radius,
+                player: def.player,
+            },
+            Mesh2d(meshes.add(Circle::new(def.radius))),
+            MeshMaterial2d(materials.add(def.kind.color().with_alpha(0.25))),
+            Transform::from_translation(Vec2::from(def.position).extend(layers::MAP + 0.3)),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn pull_shapes(
+    hazards: Query<(&Hazard, &Transform)>,
+    mut shapes: Query<
+        (&Transform, &ComputedMass, &mut ExternalForce),
+        (With<Enemy>, Without<ColliderDisabled>),
+    >,
+) {
+    for (hazard, hazard_transform) in &hazards {
+        if hazard.kind.pull() == 0.0 {
+            continue;
+        }
+        let center = hazard_transform.translation.truncate();
+        for (transform, mass, mut force) in &mut shapes {
+            let acceleration = hazard.acceleration(center, transform.translation.truncate());
+            if acceleration != Vec2::ZERO {
+                force.apply_force(acceleration * mass.value());
+            }
+        }
+    }
+}
+
+fn pull_player(
+    time: Res<Time>,
+    hazards: Query<(&Hazard, &Transform), Without<Player>>,
+    player: Single<(&Transform, &mut Velocity), With<Player>>,
+) {
+    let (transform, mut velocity) = player.into_inner();
+    for (hazard, hazard_transform) in &hazards {
+        if hazard.player {
+            let center = hazard_transform.translation.truncate();
+            velocity.0 +=
+                hazard.acceleration(center, transform.translation.truncate()) * time.delta_secs();
+        }
+    }
+}
+
+fn destroy_shapes(
+    mut commands: Commands,
+    hazards: Query<(&Hazard, &Transform)>,
+    shapes: Query<(Entity, &Transform), (With<Enemy>, Without<ColliderDisabled>)>,
+) {
+    for (entity, transform) in &shapes {
+        let position = transform.translation.truncate();
+        let destroyed = hazards.iter().any(|(hazard, hazard_transform)| {
+            hazard.kind == HazardKind::Destroy
+                && hazard.contains(hazard_transform.translation.truncate(), position)
+        });
+        if destroyed {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn destroy_player(
+    hazards: Query<(&Hazard, &Transform), Without<Player>>,
+    player: Single<&Transform, With<Player>>,
+    mut next_state: ResMut<NextState<Playing>>,
+) {
+    let position = player.translation.truncate();
+    let touching = hazards.iter().any(|(hazard, transform)| {
+        hazard.player
+            && hazard.kind == HazardKind::Destroy
+            && hazard.contains(transform.translation.truncate(), position)
+    });
+    if touching {
+        next_state.set(Playing::Dying);
+    }
+}
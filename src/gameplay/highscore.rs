@@ -0,0 +1,394 @@
+//! High score tables that persist between runs.
+
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    platform::collections::HashMap,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        mode::{GameMode, RunSeed},
+        score::LiveScore,
+    },
+    screens::Screen,
+    storage,
+    theme::widget,
+};
+
+pub(crate) struct HighScorePlugin;
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load())
+            .init_resource::<NameEntry>()
+            .add_systems(OnEnter(Screen::Score), reset_name_entry)
+            .add_systems(Update, type_name.run_if(in_state(Screen::Score)));
+    }
+}
+
+const FILE_NAME: &str = "high_scores.ron";
+/// Unreadable high score files are moved here before they are overwritten
+const BACKUP_FILE_NAME: &str = "high_scores.ron.bak";
+/// Bump this when the file format changes and handle the old version in [`HighScores::from_file`].
+const FILE_VERSION: u32 = 1;
+/// Number of scores kept per table
+const MAX_ENTRIES: usize = 10;
+const MAX_NAME_LENGTH: usize = 12;
+
+/// Scores are only compared against runs with the same mode and seed
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct ScoreKey {
+    pub mode: GameMode,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl ScoreKey {
+    pub fn new(mode: GameMode, seed: RunSeed) -> Self {
+        Self { mode, seed: seed.0 }
+    }
+
+    pub fn name(&self) -> String {
+        match self.seed {
+            Some(seed) => format!("{} (seed {seed})", self.mode.name()),
+            None => self.mode.name().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct HighScoreEntry {
+    pub name: String,
+    pub points: usize,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct HighScores {
+    tables: HashMap<ScoreKey, Vec<HighScoreEntry>>,
+    /// false when the file on disk couldn't be understood or backed up, so it isn't clobbered
+    writable: bool,
+}
+
+/// On disk representation of [`HighScores`]
+#[derive(Serialize, Deserialize)]
+struct HighScoreFile {
+    /// missing in files saved before the format was versioned
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    tables: Vec<HighScoreTable>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreTable {
+    key: ScoreKey,
+    #[serde(default)]
+    entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    fn load() -> Self {
+        let Some(contents) = storage::load(FILE_NAME) else {
+            return Self {
+                writable: true,
+                ..default()
+            };
+        };
+
+        match Self::parse(&contents) {
+            Ok(scores) => scores,
+            Err(err) => {
+                warn!("Could not read high scores, starting a new table: {err}");
+                // keep the old file around instead of silently losing it
+                if let Err(err) = storage::save(BACKUP_FILE_NAME, &contents) {
+                    warn!("Could not back up high scores, they will not be saved: {err}");
+                    return Self::default();
+                }
+                Self {
+                    writable: true,
+                    ..default()
+                }
+            }
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str::<HighScoreFile>(contents).map(Self::from_file)
+    }
+
+    fn from_file(file: HighScoreFile) -> Self {
+        if file.version > FILE_VERSION {
+            warn!(
+                "High scores were saved by a newer version ({}), some data may be lost",
+                file.version
+            );
+        } else if file.version < FILE_VERSION {
+            // older files have the same tables, they are rewritten in the new format on the next save
+            info!(
+                "Migrating high scores from version {} to {FILE_VERSION}",
+                file.version
+            );
+        }
+
+        let mut scores = Self {
+            writable: true,
+            ..default()
+        };
+        for table in file.tables {
+            for entry in table.entries {
+                scores.insert(table.key, entry);
+            }
+        }
+        scores
+    }
+
+    fn to_contents(&self) -> Result<String, ron::Error> {
+        let file = HighScoreFile {
+            version: FILE_VERSION,
+            tables: self
+                .tables()
+                .map(|(key, entries)| HighScoreTable {
+                    key: *key,
+                    entries: entries.to_vec(),
+                })
+                .collect(),
+        };
+        ron::ser::to_string_pretty(&file, default())
+    }
+
+    fn save(&self) {
+        if !self.writable {
+            return;
+        }
+
+        let contents = match self.to_contents() {
+            Ok(contents) => contents,
+            Err(err) => {
+                warn!("Could not serialize high scores: {err}");
+                return;
+            }
+        };
+        if let Err(err) = storage::save(FILE_NAME, &contents) {
+            warn!("Could not save high scores: {err}");
+        }
+    }
+
+    /// whether `points` would make it onto the table for `key`
+    pub fn qualifies(&self, key: ScoreKey, points: usize) -> bool {
+        if points == 0 {
+            return false;
+        }
+        self.tables.get(&key).is_none_or(|entries| {
+            entries.len() < MAX_ENTRIES || entries.last().is_some_and(|e| points > e.points)
+        })
+    }
+
+    pub fn insert(&mut self, key: ScoreKey, entry: HighScoreEntry) {
+        let entries = self.tables.entry(key).or_default();
+        let index = entries.partition_point(|e| e.points >= entry.points);
+        entries.insert(index, entry);
+        entries.truncate(MAX_ENTRIES);
+    }
+
+    /// All tables, sorted by mode and seed
+    pub fn tables(&self) -> impl Iterator<Item = (&ScoreKey, &[HighScoreEntry])> {
+        let mut tables = self
+            .tables
+            .iter()
+            .map(|(key, entries)| (key, entries.as_slice()))
+            .collect::<Vec<_>>();
+        tables.sort_by_key(|(key, _)| (key.mode.name(), key.seed));
+        tables.into_iter()
+    }
+}
+
+/// Name being typed on the score screen
+#[derive(Resource, Default)]
+struct NameEntry {
+    name: String,
+    submitted: bool,
+}
+
+#[derive(Component)]
+struct NameEntryText;
+
+fn reset_name_entry(mut entry: ResMut<NameEntry>) {
+    *entry = NameEntry::default();
+}
+
+/// Name entry shown on the score screen when the score makes it onto the table
+pub(crate) fn name_entry() -> impl Bundle {
+    (
+        Name::new("Name Entry"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            widget::label("New high score! Type your name and press Enter"),
+            (widget::label(name_text("")), NameEntryText),
+        ],
+    )
+}
+
+fn name_text(name: &str) -> String {
+    format!("Name: {name}_")
+}
+
+fn type_name(
+    mut keys: EventReader<KeyboardInput>,
+    mut entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    live_score: Res<LiveScore>,
+    mode: Res<GameMode>,
+    seed: Res<RunSeed>,
+    mut text: Query<&mut Text, With<NameEntryText>>,
+) {
+    // nothing to type into if the score didn't qualify
+    let Ok(mut text) = text.single_mut() else {
+        keys.clear();
+        return;
+    };
+    if entry.submitted {
+        keys.clear();
+        return;
+    }
+
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => {
+                let name = entry.name.trim();
+                let name = if name.is_empty() { "Anonymous" } else { name };
+                high_scores.insert(
+                    ScoreKey::new(*mode, *seed),
+                    HighScoreEntry {
+                        name: name.to_string(),
+                        points: live_score.points,
+                    },
+                );
+                high_scores.save();
+                text.0 = format!("Saved as {name}");
+                entry.submitted = true;
+                return;
+            }
+            Key::Backspace => {
+                entry.name.pop();
+            }
+            Key::Space if entry.name.chars().count() < MAX_NAME_LENGTH => {
+                entry.name.push(' ');
+            }
+            Key::Character(c) => {
+                for c in c.chars().filter(|c| !c.is_control()) {
+                    if entry.name.chars().count() < MAX_NAME_LENGTH {
+                        entry.name.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+        text.0 = name_text(&entry.name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDLESS: ScoreKey = ScoreKey {
+        mode: GameMode::Endless,
+        seed: None,
+    };
+
+    fn entry(name: &str, points: usize) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.to_string(),
+            points,
+        }
+    }
+
+    fn points(scores: &HighScores, key: ScoreKey) -> Vec<usize> {
+        scores.tables[&key].iter().map(|e| e.points).collect()
+    }
+
+    #[test]
+    fn insert_keeps_the_best_scores_in_order() {
+        let mut scores = HighScores::default();
+        for points in 1..=MAX_ENTRIES + 2 {
+            scores.insert(ENDLESS, entry("a", points * 10));
+        }
+        // ties go after the score that was there first
+        scores.insert(ENDLESS, entry("b", 50));
+
+        let table = &scores.tables[&ENDLESS];
+        assert_eq!(table.len(), MAX_ENTRIES);
+        assert_eq!(table[0].points, (MAX_ENTRIES + 2) * 10);
+        assert!(
+            table
+                .windows(2)
+                .all(|pair| pair[0].points >= pair[1].points)
+        );
+        let tie = table.iter().position(|e| e.points == 50).unwrap();
+        assert_eq!(
+            (table[tie].name.as_str(), table[tie + 1].name.as_str()),
+            ("a", "b")
+        );
+    }
+
+    #[test]
+    fn qualifies_until_the_table_is_full() {
+        let mut scores = HighScores::default();
+        assert!(!scores.qualifies(ENDLESS, 0));
+        assert!(scores.qualifies(ENDLESS, 1));
+
+        for _ in 0..MAX_ENTRIES {
+            scores.insert(ENDLESS, entry("a", 100));
+        }
+        assert!(!scores.qualifies(ENDLESS, 100));
+        assert!(scores.qualifies(ENDLESS, 101));
+        // each seed has its own table
+        assert!(scores.qualifies(ScoreKey::new(GameMode::Endless, RunSeed(Some(1))), 1));
+    }
+
+    #[test]
+    fn saved_scores_read_back() {
+        let mut scores = HighScores::default();
+        scores.insert(ENDLESS, entry("a", 30));
+        scores.insert(ENDLESS, entry("b", 20));
+        let seeded = ScoreKey::new(GameMode::Endless, RunSeed(Some(7)));
+        scores.insert(seeded, entry("c", 10));
+
+        let contents = scores.to_contents().unwrap();
+        assert!(contents.contains(&format!("version: {FILE_VERSION}")));
+        let loaded = HighScores::parse(&contents).unwrap();
+        assert!(loaded.writable);
+        assert_eq!(points(&loaded, ENDLESS), [30, 20]);
+        assert_eq!(points(&loaded, seeded), [10]);
+    }
+
+    #[test]
+    fn other_versions_are_read_and_saved_again() {
+        let table = "tables: [(key: (mode: Endless), entries: [(name: \"a\", points: 5)])]";
+        for contents in [
+            format!("({table})"),
+            format!("(version: 0, {table})"),
+            format!("(version: {}, {table})", FILE_VERSION + 1),
+        ] {
+            let loaded = HighScores::parse(&contents).unwrap();
+            assert!(loaded.writable, "{contents}");
+            assert_eq!(points(&loaded, ENDLESS), [5]);
+        }
+    }
+
+    #[test]
+    fn unreadable_files_are_rejected() {
+        assert!(HighScores::parse("not high scores").is_err());
+    }
+}
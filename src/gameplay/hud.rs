@@ -0,0 +1,237 @@
+//! Heads up display shown while playing.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    gameplay::{
+        enemy::{EnemyType, ShapeCounts},
+        ink::Ink,
+        mode::{GameMode, TargetProgress},
+        path::DrawPath,
+        player::Player,
+        score::LiveScore,
+        state::SurvivalTime,
+        territory::{PenColor, Territory},
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+pub(crate) struct HudPlugin;
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Screen::Gameplay), spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    update_counts.run_if(resource_changed::<ShapeCounts>),
+                    update_score.run_if(resource_changed::<LiveScore>),
+                    update_survival_time.run_if(resource_changed::<SurvivalTime>),
+                    update_drawing,
+                    update_ink,
+                    update_objective.run_if(resource_changed::<TargetProgress>),
+                    update_territory.run_if(resource_exists_and_equals(GameMode::Territory)),
+                )
+                    .run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// Shape types shown in the HUD, in display order
+const HUD_SHAPES: [EnemyType; 7] = [
+    EnemyType::White,
+    EnemyType::Red,
+    EnemyType::Green,
+    EnemyType::Blue,
+    EnemyType::Purple,
+    EnemyType::Yellow,
+    EnemyType::Cyan,
+];
+
+#[derive(Component)]
+struct HudCount(EnemyType);
+
+#[derive(Component)]
+struct HudScore;
+
+#[derive(Component)]
+struct HudSurvivalTime;
+
+#[derive(Component)]
+struct HudDrawing;
+
+#[derive(Component)]
+struct HudInk;
+
+#[derive(Component)]
+struct HudObjective;
+
+#[derive(Component)]
+struct HudTerritory;
+
+fn spawn_hud(mut commands: Commands, mode: Res<GameMode>) {
+    commands
+        .spawn((
+            Name::new("Hud"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Px(10.0),
+                top: Px(10.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Px(4.0),
+                ..default()
+            },
+            Pickable::IGNORE,
+            StateScoped(Screen::Gameplay),
+            children![
+                (widget::label("Score: 0"), HudScore),
+                (widget::label("Time: 0:00"), HudSurvivalTime),
+                (widget::label(drawing_text(false)), HudDrawing),
+                (widget::label(ink_text(1.0)), HudInk),
+            ],
+        ))
+        .with_children(|parent| {
+            if let Some((target, count)) = mode.target() {
+                parent.spawn((
+                    widget::label(objective_text(target, 0, count)),
+                    HudObjective,
+                ));
+            }
+            if *mode == GameMode::Territory {
+                parent.spawn((
+                    widget::label(territory_text(PenColor::default().0, 0, 0)),
+                    HudTerritory,
+                ));
+            }
+            parent
+                .spawn((
+                    Name::new("Hud Counts"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Px(12.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    for t in HUD_SHAPES {
+                        row.spawn(count_text(t));
+                    }
+                });
+        });
+}
+
+fn count_text(t: EnemyType) -> impl Bundle {
+    (
+        Name::new("Hud Count"),
+        Text(format!("{t:?} 0")),
+        TextFont::from_font_size(20.0),
+        TextColor(t.color()),
+        HudCount(t),
+    )
+}
+
+fn drawing_text(active: bool) -> String {
+    if active {
+        "Drawing".to_string()
+    } else {
+        "Not drawing".to_string()
+    }
+}
+
+fn ink_text(fraction: f32) -> String {
+    format!("Ink: {:.0}%", fraction * 100.0)
+}
+
+fn objective_text(target: EnemyType, made: usize, count: usize) -> String {
+    format!("Make {target:?}: {made}/{count}")
+}
+
+fn territory_text(pen: EnemyType, held: usize, contested: usize) -> String {
+    format!("Pen: {pen:?} (Tab)  Territory: {held} hexes, {contested} contested")
+}
+
+fn update_counts(counts: Res<ShapeCounts>, mut texts: Query<(&HudCount, &mut Text)>) {
+    for (HudCount(t), mut text) in &mut texts {
+        text.0 = format!("{t:?} {}", counts.get(*t));
+    }
+}
+
+fn update_score(score: Res<LiveScore>, mut text: Single<&mut Text, With<HudScore>>) {
+    text.0 = if score.combo > 1 {
+        format!("Score: {} (combo x{})", score.points, score.combo)
+    } else {
+        format!("Score: {}", score.points)
+    };
+}
+
+fn update_survival_time(
+    survival_time: Res<SurvivalTime>,
+    mode: Res<GameMode>,
+    mut text: Single<&mut Text, With<HudSurvivalTime>>,
+    mut shown_secs: Local<Option<u64>>,
+) {
+    // count down when the mode has a time limit
+    let (label, secs) = match mode.time_limit() {
+        Some(limit) => (
+            "Time left",
+            (limit - survival_time.elapsed_secs()).max(0.0).ceil() as u64,
+        ),
+        None => ("Time", survival_time.elapsed().as_secs()),
+    };
+
+    // only touch the text when the displayed value changes
+    if *shown_secs == Some(secs) {
+        return;
+    }
+    *shown_secs = Some(secs);
+    text.0 = format!("{label}: {}:{:02}", secs / 60, secs % 60);
+}
+
+fn update_objective(
+    mode: Res<GameMode>,
+    progress: Res<TargetProgress>,
+    mut text: Query<&mut Text, With<HudObjective>>,
+) {
+    let (Some((target, count)), Ok(mut text)) = (mode.target(), text.single_mut()) else {
+        return;
+    };
+    text.0 = objective_text(target, **progress, count);
+}
+
+fn update_territory(
+    territory: Res<Territory>,
+    pen: Query<Ref<PenColor>, With<Player>>,
+    mut text: Single<&mut Text, With<HudTerritory>>,
+) {
+    let Ok(pen) = pen.single() else {
+        return;
+    };
+    if !territory.is_changed() && !pen.is_changed() {
+        return;
+    }
+    text.0 = territory_text(pen.0, territory.held(), territory.contested());
+}
+
+fn update_ink(
+    ink: Res<Ink>,
+    mut text: Single<&mut Text, With<HudInk>>,
+    mut shown_percent: Local<Option<u32>>,
+) {
+    // ink changes a little nearly every frame, the text only needs redoing when the percentage does
+    let percent = (ink.fraction() * 100.0).round() as u32;
+    if *shown_percent == Some(percent) {
+        return;
+    }
+    *shown_percent = Some(percent);
+    text.0 = ink_text(ink.fraction());
+}
+
+fn update_drawing(
+    player: Query<&DrawPath, (With<Player>, Changed<DrawPath>)>,
+    mut text: Single<&mut Text, With<HudDrawing>>,
+) {
+    let Ok(draw) = player.single() else {
+        return;
+    };
+    text.0 = drawing_text(draw.active());
+}
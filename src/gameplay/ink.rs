@@ -0,0 +1,110 @@
+//! Ink the pen uses up while drawing.
+
+use bevy::prelude::*;
+
+use crate::{
+    PausableSystems,
+    gameplay::{
+        path::{DrawPath, ShapesCombined},
+        player::Player,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct InkPlugin;
+impl Plugin for InkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InkSettings>()
+            .init_resource::<Ink>()
+            .add_systems(OnEnter(Screen::Gameplay), reset_ink)
+            .add_systems(
+                Update,
+                (refill_over_time, refill_on_combine)
+                    .in_set(PausableSystems)
+                    .run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+/// How much ink there is and how it comes back
+#[derive(Resource)]
+pub(crate) struct InkSettings {
+    /// path length a full pen can draw
+    pub max: f32,
+    /// ink regained each second while not drawing
+    pub refill_rate: f32,
+    /// ink regained for each combine
+    pub combine_refill: f32,
+}
+
+impl Default for InkSettings {
+    fn default() -> Self {
+        Self {
+            max: 1500.0,
+            refill_rate: 150.0,
+            combine_refill: 300.0,
+        }
+    }
+}
+
+/// Ink left in the pen
+#[derive(Resource, Default)]
+pub(crate) struct Ink {
+    pub amount: f32,
+    pub max: f32,
+}
+
+impl Ink {
+    /// use up to `length` of ink, returning how much was available
+    pub fn spend(&mut self, length: f32) -> f32 {
+        let spent = length.min(self.amount);
+        self.amount -= spent;
+        spent
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amount <= 0.0
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.amount / self.max
+        } else {
+            0.0
+        }
+    }
+
+    fn refill(&mut self, amount: f32) {
+        self.amount = (self.amount + amount).min(self.max);
+    }
+}
+
+fn reset_ink(mut commands: Commands, settings: Res<InkSettings>) {
+    commands.insert_resource(Ink {
+        amount: settings.max,
+        max: settings.max,
+    });
+}
+
+fn refill_over_time(
+    time: Res<Time>,
+    settings: Res<InkSettings>,
+    pen: Single<&DrawPath, With<Player>>,
+    mut ink: ResMut<Ink>,
+) {
+    // the pen only refills while it is lifted
+    if pen.active() || ink.amount >= ink.max {
+        return;
+    }
+    ink.refill(settings.refill_rate * time.delta_secs());
+}
+
+fn refill_on_combine(
+    mut combined: EventReader<ShapesCombined>,
+    settings: Res<InkSettings>,
+    mut ink: ResMut<Ink>,
+) {
+    for _ in combined.read() {
+        ink.refill(settings.combine_refill);
+    }
+}
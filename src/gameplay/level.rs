@@ -0,0 +1,828 @@
+//! Authored levels loaded from `.level.ron` files.
+
+use avian2d::prelude::LinearVelocity;
+use bevy::{
+    asset::{AssetLoadFailedEvent, AssetLoader, LoadContext, io::Reader},
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use hexx::{Hex, HexLayout};
+use rand::{Rng, seq::IteratorRandom};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    gameplay::{
+        cutter::SpawnCutters,
+        decoration::DecorationDef,
+        enemy::{EnemyHandles, EnemyType, SpawnEnemies},
+        enemy_ai::FollowPlayer,
+        flow::FlowDef,
+        goal::{GoalPenDef, HerdedShapes},
+        hazard::{HazardDef, HazardKind},
+        path::{PathPhysics, ShapesCombined},
+        score::LiveScore,
+        state::{Playing, RunOutcome, SurvivalTime},
+        terrain::Terrain,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct LevelPlugin;
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .init_resource::<ActiveLevel>()
+            .init_resource::<LevelProgress>()
+            .add_systems(
+                OnEnter(Screen::Gameplay),
+                (reset_level_progress, spawn_level_shapes),
+            )
+            .add_systems(Update, report_level_errors)
+            .add_systems(
+                Update,
+                (spawn_waves, check_objectives)
+                    .run_if(in_state(Screen::Gameplay))
+                    .run_if(in_state(Playing::Live)),
+            );
+    }
+}
+
+/// Level that is loaded when nothing else is selected
+const DEFAULT_LEVEL: &str = "levels/default.level.ron";
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct Level {
+    pub name: String,
+    pub map: MapDef,
+    /// shapes placed when the level starts. random shapes are spawned when this is empty
+    #[serde(default)]
+    pub shapes: Vec<ShapeDef>,
+    #[serde(default)]
+    pub spawns: Vec<SpawnWave>,
+    /// the level is won when all of these are complete
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /// seconds to complete the objectives in for a time bonus
+    #[serde(default)]
+    pub par_time: Option<f32>,
+    /// whether shapes bounce off paths or pass through them
+    #[serde(default)]
+    pub path_physics: PathPhysics,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            map: MapDef::default(),
+            shapes: Vec::new(),
+            spawns: Vec::new(),
+            objectives: Vec::new(),
+            par_time: None,
+            path_physics: PathPhysics::default(),
+        }
+    }
+}
+
+/// Size and layout of the hex map
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct MapDef {
+    /// number of rings of hexes around the center
+    pub radius: u32,
+    /// hexes within `radius` that are cut out of the map
+    #[serde(default)]
+    pub removed: Vec<[i32; 2]>,
+    #[serde(default = "default_hex_size")]
+    pub hex_size: f32,
+    /// random shapes spawn within this many rings of the center
+    #[serde(default)]
+    pub spawn_rings: Option<u32>,
+    /// terrain of hexes that aren't in `tiles`
+    #[serde(default)]
+    pub terrain: Terrain,
+    #[serde(default)]
+    pub tiles: Vec<TileDef>,
+    #[serde(default)]
+    pub zones: Vec<ZoneDef>,
+    #[serde(default)]
+    pub flow: FlowDef,
+    #[serde(default)]
+    pub decoration: DecorationDef,
+    /// pens shapes can be herded into
+    #[serde(default)]
+    pub goals: Vec<GoalPenDef>,
+    #[serde(default)]
+    pub hazards: Vec<HazardDef>,
+}
+
+/// Terrain of a single hex
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct TileDef {
+    pub hex: [i32; 2],
+    pub terrain: Terrain,
+}
+
+/// A hex with special meaning
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct ZoneDef {
+    pub hex: [i32; 2],
+    pub kind: ZoneKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ZoneKind {
+    /// random shapes spawn here instead of around the center
+    Spawn,
+}
+
+fn default_hex_size() -> f32 {
+    50.0
+}
+
+impl Default for MapDef {
+    fn default() -> Self {
+        Self {
+            radius: 15,
+            removed: Vec::new(),
+            hex_size: default_hex_size(),
+            spawn_rings: Some(4),
+            terrain: Terrain::default(),
+            tiles: Vec::new(),
+            zones: Vec::new(),
+            flow: FlowDef::default(),
+            decoration: DecorationDef::default(),
+            goals: Vec::new(),
+            hazards: Vec::new(),
+        }
+    }
+}
+
+impl MapDef {
+    pub fn layout(&self) -> HexLayout {
+        HexLayout::flat()
+            .with_hex_size(self.hex_size)
+            .with_origin(Vec2::ZERO)
+    }
+
+    pub fn hexes(&self) -> impl Iterator<Item = Hex> {
+        Hex::ZERO
+            .range(self.radius)
+            .filter(|hex| !self.removed.contains(&hex.to_array()))
+    }
+
+    /// hexes just off the map, where it needs walls to keep things in
+    pub fn border(&self) -> impl Iterator<Item = Hex> {
+        Hex::ZERO.range(self.radius + 1).filter(|hex| {
+            !self.contains_hex(*hex) && hex.all_neighbors().iter().any(|n| self.contains_hex(*n))
+        })
+    }
+
+    /// area covered by the map's hexes
+    pub fn bounds(&self) -> Rect {
+        let layout = self.layout();
+        self.hexes()
+            .flat_map(|hex| layout.hex_corners(hex))
+            .fold(Rect::EMPTY, |bounds, corner| bounds.union_point(corner))
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.contains_hex(self.layout().world_pos_to_hex(position))
+    }
+
+    pub fn contains_hex(&self, hex: Hex) -> bool {
+        hex.ulength() <= self.radius && !self.removed.contains(&hex.to_array())
+    }
+
+    pub fn terrain(&self, hex: Hex) -> Terrain {
+        self.tiles
+            .iter()
+            .find(|tile| tile.hex == hex.to_array())
+            .map_or(self.terrain, |tile| tile.terrain)
+    }
+
+    pub fn set_terrain(&mut self, hex: Hex, terrain: Terrain) {
+        self.tiles.retain(|tile| tile.hex != hex.to_array());
+        if terrain != self.terrain {
+            self.tiles.push(TileDef {
+                hex: hex.to_array(),
+                terrain,
+            });
+        }
+    }
+
+    pub fn is_wall(&self, hex: Hex) -> bool {
+        self.terrain(hex) == Terrain::Wall
+    }
+
+    pub fn zone(&self, hex: Hex) -> Option<ZoneKind> {
+        self.zones
+            .iter()
+            .find(|zone| zone.hex == hex.to_array())
+            .map(|zone| zone.kind)
+    }
+
+    /// random position near the center of a hex shapes are allowed to spawn in
+    pub fn random_spawn_position(&self, rng: &mut impl Rng) -> Vec2 {
+        let spawn_zones = self
+            .zones
+            .iter()
+            .filter(|zone| zone.kind == ZoneKind::Spawn)
+            .map(|zone| Hex::from(zone.hex));
+        let hex = if spawn_zones.clone().next().is_some() {
+            spawn_zones.choose(rng)
+        } else {
+            let rings = self.spawn_rings.unwrap_or(self.radius).min(self.radius);
+            Hex::ZERO
+                .range(rings)
+                .filter(|hex| self.contains_hex(*hex) && !self.is_wall(*hex))
+                .choose(rng)
+        }
+        .unwrap_or(Hex::ZERO);
+        let jitter = self.hex_size / 2.0;
+        self.layout().hex_to_world_pos(hex)
+            + Vec2::new(
+                rng.gen_range(-jitter..jitter),
+                rng.gen_range(-jitter..jitter),
+            )
+    }
+}
+
+/// A shape placed by the level
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ShapeDef {
+    pub kind: EnemyType,
+    pub position: [f32; 2],
+    #[serde(default)]
+    pub velocity: [f32; 2],
+    #[serde(default)]
+    pub behaviour: Behaviour,
+}
+
+/// How a placed shape reacts to the player
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Behaviour {
+    /// whatever the shape's type normally does
+    #[default]
+    Default,
+    /// ignore the player
+    Drift,
+    /// move towards the player when close
+    Chase,
+    /// move away from the player when close
+    Flee,
+}
+
+impl Behaviour {
+    pub const ALL: [Behaviour; 4] = [
+        Behaviour::Default,
+        Behaviour::Drift,
+        Behaviour::Chase,
+        Behaviour::Flee,
+    ];
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|b| b == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl ShapeDef {
+    pub fn spawn(&self, commands: &mut Commands, handles: &EnemyHandles) -> Entity {
+        let id = self.kind.spawn(
+            commands,
+            Transform::from_translation(Vec2::from(self.position).extend(0.0)),
+            LinearVelocity(Vec2::from(self.velocity)),
+            handles,
+        );
+        match self.behaviour {
+            Behaviour::Default => {}
+            Behaviour::Drift => {
+                commands.entity(id).remove::<FollowPlayer>();
+            }
+            Behaviour::Chase => {
+                commands.entity(id).insert(FollowPlayer {
+                    acceleration: 2000.0,
+                    distance: 150.0,
+                });
+            }
+            Behaviour::Flee => {
+                commands.entity(id).insert(FollowPlayer {
+                    acceleration: -2000.0,
+                    distance: 150.0,
+                });
+            }
+        }
+        id
+    }
+}
+
+/// Shapes that spawn some time after the level starts
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct SpawnWave {
+    /// seconds after the start of the level
+    pub at: f32,
+    /// repeat the wave with this many seconds between spawns
+    #[serde(default)]
+    pub every: Option<f32>,
+    #[serde(default)]
+    pub shapes: Vec<ShapeDef>,
+    /// number of random primaries to spawn
+    #[serde(default)]
+    pub random: usize,
+    /// number of path cutters to spawn
+    #[serde(default)]
+    pub cutters: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) enum Objective {
+    /// combine shapes into `count` of `kind`
+    Make { kind: EnemyType, count: usize },
+    /// stay alive for this many seconds
+    Survive { seconds: f32 },
+    /// reach this many points
+    Score { points: usize },
+    /// have `count` of `kind` in goal pens at the same time
+    Herd { kind: EnemyType, count: usize },
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum LevelError {
+    #[error("could not read level: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("invalid level: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+impl Level {
+    /// Check the level for mistakes that parsing can't catch
+    pub fn validate(&self) -> Result<(), LevelError> {
+        let mut errors = Vec::new();
+
+        if self.map.radius == 0 {
+            errors.push("map radius must be at least 1".to_string());
+        }
+        if self.map.hex_size <= 0.0 {
+            errors.push(format!(
+                "map hex_size must be positive, got {}",
+                self.map.hex_size
+            ));
+        }
+        if let Some(rings) = self.map.spawn_rings.filter(|r| *r > self.map.radius) {
+            errors.push(format!(
+                "map spawn_rings ({rings}) is larger than the map radius ({})",
+                self.map.radius
+            ));
+        }
+
+        if !self.map.contains_hex(Hex::ZERO) || self.map.is_wall(Hex::ZERO) {
+            errors.push("the center of the map must be open for the player".to_string());
+        }
+        if self.map.terrain == Terrain::Wall {
+            errors.push("map terrain can't be Wall".to_string());
+        }
+        for (i, tile) in self.map.tiles.iter().enumerate() {
+            if !self.map.contains_hex(Hex::from(tile.hex)) {
+                errors.push(format!(
+                    "{:?} tile at {:?} is outside the map",
+                    tile.terrain, tile.hex
+                ));
+            }
+            if self.map.tiles[..i]
+                .iter()
+                .any(|other| other.hex == tile.hex)
+            {
+                errors.push(format!("more than one tile at {:?}", tile.hex));
+            }
+        }
+        for current in &self.map.flow.currents {
+            if !self.map.contains_hex(Hex::from(current.hex)) {
+                errors.push(format!("current at {:?} is outside the map", current.hex));
+            }
+        }
+        for canopy in &self.map.decoration.canopies {
+            if !self.map.contains_hex(Hex::from(canopy.hex)) {
+                errors.push(format!("canopy at {:?} is outside the map", canopy.hex));
+            }
+            if canopy.size <= 0.0 {
+                errors.push(format!(
+                    "canopy at {:?} must have a positive size, got {}",
+                    canopy.hex, canopy.size
+                ));
+            }
+        }
+        for (i, goal) in self.map.goals.iter().enumerate() {
+            if goal.hexes.is_empty() {
+                errors.push(format!("goal pen {i} has no hexes"));
+            }
+            if goal.accepts == EnemyType::None {
+                errors.push(format!("goal pen {i} must accept a shape type"));
+            }
+            for hex in &goal.hexes {
+                if !self.map.contains_hex(Hex::from(*hex)) {
+                    errors.push(format!("goal pen {i}: hex {hex:?} is outside the map"));
+                } else if self.map.is_wall(Hex::from(*hex)) {
+                    errors.push(format!("goal pen {i}: hex {hex:?} is on a wall"));
+                }
+            }
+        }
+        for (i, hazard) in self.map.hazards.iter().enumerate() {
+            if !self.map.contains(Vec2::from(hazard.position)) {
+                errors.push(format!(
+                    "hazard {i} at {:?} is outside the map",
+                    hazard.position
+                ));
+            }
+            if hazard.radius <= 0.0 {
+                errors.push(format!(
+                    "hazard {i} must have a positive radius, got {}",
+                    hazard.radius
+                ));
+            }
+            if hazard.player
+                && hazard.kind == HazardKind::Destroy
+                && Vec2::from(hazard.position).length() < hazard.radius
+            {
+                errors.push(format!("hazard {i} would kill the player where they start"));
+            }
+        }
+        for zone in &self.map.zones {
+            if !self.map.contains_hex(Hex::from(zone.hex)) {
+                errors.push(format!(
+                    "{:?} zone at {:?} is outside the map",
+                    zone.kind, zone.hex
+                ));
+            } else if self.map.is_wall(Hex::from(zone.hex)) {
+                errors.push(format!(
+                    "{:?} zone at {:?} is on a wall",
+                    zone.kind, zone.hex
+                ));
+            }
+        }
+
+        let wave_shapes = self
+            .spawns
+            .iter()
+            .enumerate()
+            .flat_map(|(i, wave)| wave.shapes.iter().map(move |s| (format!("wave {i}"), s)));
+        let level_shapes = self.shapes.iter().map(|s| ("shapes".to_string(), s));
+        for (i, (source, shape)) in level_shapes.chain(wave_shapes).enumerate() {
+            if shape.kind == EnemyType::None {
+                errors.push(format!("{source}: shape {i} has no type"));
+            }
+            let position = Vec2::from(shape.position);
+            if !self.map.contains(position) {
+                errors.push(format!(
+                    "{source}: {:?} at {:?} is outside the map",
+                    shape.kind, shape.position
+                ));
+            } else if self
+                .map
+                .is_wall(self.map.layout().world_pos_to_hex(position))
+            {
+                errors.push(format!(
+                    "{source}: {:?} at {:?} is inside a wall",
+                    shape.kind, shape.position
+                ));
+            }
+        }
+
+        for (i, wave) in self.spawns.iter().enumerate() {
+            if wave.at < 0.0 {
+                errors.push(format!("wave {i} starts at a negative time ({})", wave.at));
+            }
+            if wave.every.is_some_and(|every| every <= 0.0) {
+                errors.push(format!("wave {i} must repeat after a positive time"));
+            }
+            if wave.shapes.is_empty() && wave.random == 0 && wave.cutters == 0 {
+                errors.push(format!("wave {i} does not spawn anything"));
+            }
+        }
+
+        for (i, objective) in self.objectives.iter().enumerate() {
+            match objective {
+                Objective::Make { kind, count } => {
+                    if *count == 0 {
+                        errors.push(format!("objective {i} asks for zero shapes"));
+                    }
+                    if matches!(
+                        kind,
+                        EnemyType::Red | EnemyType::Green | EnemyType::Blue | EnemyType::None
+                    ) {
+                        errors.push(format!("objective {i}: {kind:?} can't be made"));
+                    }
+                }
+                Objective::Herd { kind, count } => {
+                    if *count == 0 {
+                        errors.push(format!("objective {i} asks for zero shapes"));
+                    }
+                    if !self.map.goals.iter().any(|goal| goal.accepts == *kind) {
+                        errors.push(format!("objective {i}: no goal pen accepts {kind:?}"));
+                    }
+                }
+                Objective::Survive { seconds } if *seconds <= 0.0 => {
+                    errors.push(format!("objective {i} must last a positive time"));
+                }
+                _ => {}
+            }
+        }
+
+        if self.par_time.is_some_and(|par| par <= 0.0) {
+            errors.push("par_time must be positive".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(LevelError::Invalid(errors))
+        }
+    }
+}
+
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, LevelError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level = ron::de::from_bytes::<Level>(&bytes)?;
+        level.validate()?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+fn report_level_errors(mut failed: EventReader<AssetLoadFailedEvent<Level>>) {
+    for event in failed.read() {
+        error!(
+            "Level {} failed to load, the built in level will be used instead: {}",
+            event.path, event.error
+        );
+    }
+}
+
+/// The level that will be played
+#[derive(Resource)]
+pub(crate) struct ActiveLevel {
+    pub handle: Handle<Level>,
+    /// used if the level fails to load
+    fallback: Level,
+}
+
+impl ActiveLevel {
+    /// go back to playing the default level
+    pub fn reset(&mut self, assets: &AssetServer) {
+        self.handle = assets.load(DEFAULT_LEVEL);
+    }
+}
+
+impl FromWorld for ActiveLevel {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            handle: assets.load(DEFAULT_LEVEL),
+            fallback: Level::default(),
+        }
+    }
+}
+
+/// Access to the level being played
+#[derive(SystemParam)]
+pub(crate) struct CurrentLevel<'w> {
+    active: Res<'w, ActiveLevel>,
+    levels: Res<'w, Assets<Level>>,
+    assets: Res<'w, AssetServer>,
+}
+
+impl CurrentLevel<'_> {
+    /// The level to play. Gameplay waits for [`level_ready`], so the fallback is only used when
+    /// the level failed to load.
+    pub fn get(&self) -> &Level {
+        self.levels
+            .get(&self.active.handle)
+            .unwrap_or(&self.active.fallback)
+    }
+
+    /// whether the level has loaded, or failed to load and been reported
+    pub fn is_ready(&self) -> bool {
+        // generated and edited levels are added straight to the assets and never go through the
+        // asset server, so check the assets as well
+        self.levels.contains(&self.active.handle)
+            || self.assets.is_loaded_with_dependencies(&self.active.handle)
+            || self.assets.load_state(&self.active.handle).is_failed()
+    }
+}
+
+/// Run condition for when the active level can be played
+pub(crate) fn level_ready(level: CurrentLevel) -> bool {
+    level.is_ready()
+}
+
+/// Tracks spawn waves and objectives for the current level
+#[derive(Resource, Default)]
+struct LevelProgress {
+    /// number of times each wave has spawned
+    waves_spawned: HashMap<usize, u32>,
+    made: HashMap<EnemyType, usize>,
+}
+
+fn reset_level_progress(mut progress: ResMut<LevelProgress>) {
+    *progress = LevelProgress::default();
+}
+
+fn spawn_level_shapes(
+    mut commands: Commands,
+    level: CurrentLevel,
+    handles: Res<EnemyHandles>,
+    mut spawn: EventWriter<SpawnEnemies>,
+) {
+    let level = level.get();
+    if level.shapes.is_empty() {
+        spawn.write(SpawnEnemies::default());
+        return;
+    }
+
+    for shape in &level.shapes {
+        shape.spawn(&mut commands, &handles);
+    }
+}
+
+fn spawn_waves(
+    mut commands: Commands,
+    level: CurrentLevel,
+    handles: Res<EnemyHandles>,
+    survival_time: Res<SurvivalTime>,
+    mut progress: ResMut<LevelProgress>,
+    mut spawn: EventWriter<SpawnEnemies>,
+    mut spawn_cutters: EventWriter<SpawnCutters>,
+) {
+    let elapsed = survival_time.elapsed_secs();
+    for (i, wave) in level.get().spawns.iter().enumerate() {
+        let spawned = progress.waves_spawned.entry(i).or_default();
+        let next_at = match wave.every {
+            Some(every) => wave.at + every * *spawned as f32,
+            None if *spawned == 0 => wave.at,
+            None => continue,
+        };
+        if elapsed < next_at {
+            continue;
+        }
+
+        *spawned += 1;
+        for shape in &wave.shapes {
+            shape.spawn(&mut commands, &handles);
+        }
+        if wave.random > 0 {
+            spawn.write(SpawnEnemies {
+                count: Some(wave.random),
+            });
+        }
+        if wave.cutters > 0 {
+            spawn_cutters.write(SpawnCutters {
+                count: wave.cutters,
+            });
+        }
+    }
+}
+
+fn check_objectives(
+    level: CurrentLevel,
+    survival_time: Res<SurvivalTime>,
+    score: Res<LiveScore>,
+    herded: Res<HerdedShapes>,
+    mut combined: EventReader<ShapesCombined>,
+    mut progress: ResMut<LevelProgress>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_state: ResMut<NextState<Playing>>,
+) {
+    for event in combined.read() {
+        *progress.made.entry(event.new_type).or_default() += 1;
+    }
+
+    let objectives = &level.get().objectives;
+    if objectives.is_empty() {
+        return;
+    }
+
+    let complete = objectives.iter().all(|objective| match objective {
+        Objective::Make { kind, count } => {
+            progress.made.get(kind).copied().unwrap_or_default() >= *count
+        }
+        Objective::Survive { seconds } => survival_time.elapsed_secs() >= *seconds,
+        Objective::Score { points } => score.points >= *points,
+        Objective::Herd { kind, count } => herded.get(*kind) >= *count,
+    });
+    if complete {
+        *outcome = RunOutcome::TargetReached;
+        next_state.set(Playing::Finished);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// validation errors of the level, or none if it is valid
+    fn errors(level: &Level) -> Vec<String> {
+        match level.validate() {
+            Ok(()) => Vec::new(),
+            Err(LevelError::Invalid(errors)) => errors,
+            Err(error) => panic!("unexpected error {error}"),
+        }
+    }
+
+    /// shape defined the way a level file would
+    fn shape_at(x: f32, y: f32) -> ShapeDef {
+        ron::from_str(&format!("(kind: Red, position: ({x:?}, {y:?}))")).unwrap()
+    }
+
+    #[test]
+    fn default_level_is_valid() {
+        assert!(errors(&Level::default()).is_empty());
+    }
+
+    #[test]
+    fn rejects_zero_radius() {
+        let mut level = Level::default();
+        level.map.radius = 0;
+        level.map.spawn_rings = None;
+        assert!(errors(&level).iter().any(|e| e.contains("radius must be")));
+    }
+
+    #[test]
+    fn rejects_spawn_rings_outside_the_map() {
+        let mut level = Level::default();
+        level.map.spawn_rings = Some(level.map.radius + 1);
+        assert!(errors(&level).iter().any(|e| e.contains("spawn_rings")));
+    }
+
+    #[test]
+    fn rejects_shapes_outside_the_map() {
+        let mut level = Level::default();
+        level.shapes.push(shape_at(0.0, 0.0));
+        assert!(errors(&level).is_empty());
+
+        level.shapes.push(shape_at(100_000.0, 0.0));
+        let mut wave: SpawnWave = ron::from_str("(at: 1.0)").unwrap();
+        wave.shapes.push(shape_at(0.0, -100_000.0));
+        level.spawns.push(wave);
+        let errors = errors(&level);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("shapes:") && e.contains("outside"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("wave 0:") && e.contains("outside"))
+        );
+    }
+
+    #[test]
+    fn rejects_overlapping_tiles() {
+        let mut level = Level::default();
+        level.map.set_terrain(Hex::new(1, 0), Terrain::Mud);
+        level.map.set_terrain(Hex::new(2, 0), Terrain::Ice);
+        assert!(errors(&level).is_empty());
+
+        level.map.tiles.push(TileDef {
+            hex: [1, 0],
+            terrain: Terrain::Ice,
+        });
+        assert!(
+            errors(&level)
+                .iter()
+                .any(|e| e.contains("more than one tile"))
+        );
+    }
+
+    #[test]
+    fn rejects_tiles_outside_the_map() {
+        let mut level = Level::default();
+        let outside = level.map.radius as i32 + 1;
+        level.map.tiles.push(TileDef {
+            hex: [outside, 0],
+            terrain: Terrain::Mud,
+        });
+        assert!(errors(&level).iter().any(|e| e.contains("outside the map")));
+    }
+}
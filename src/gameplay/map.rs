@@ -1,71 +1,210 @@
+use avian2d::prelude::{Collider, RigidBody};
 use bevy::{
     asset::RenderAssetUsages,
+    platform::collections::HashMap,
     prelude::*,
     render::mesh::{Indices, PrimitiveTopology},
+    window::{PrimaryWindow, WindowResized},
 };
 use hexx::{Hex, HexLayout, InsetOptions, PlaneMeshBuilder};
 
-use crate::screens::Screen;
+use crate::{
+    gameplay::{Wall, enemy::Enemy, layers, level::CurrentLevel, player::Player, terrain::Terrain},
+    screens::Screen,
+};
 
 pub struct MapPlugin;
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(Screen::Gameplay), setup_grid)
-            .add_systems(OnExit(Screen::Gameplay), despawn_grid);
+        app.init_resource::<HoveredHex>()
+            .add_systems(OnEnter(Screen::Gameplay), (setup_grid, spawn_walls).chain())
+            .add_systems(
+                Update,
+                keep_inside_arena
+                    .run_if(on_event::<WindowResized>)
+                    .run_if(in_state(Screen::Gameplay)),
+            )
+            .add_systems(OnExit(Screen::Gameplay), despawn_grid)
+            .add_systems(OnEnter(Screen::Editor), (setup_grid, spawn_cursor).chain())
+            .add_systems(Update, move_cursor.run_if(in_state(Screen::Editor)))
+            .add_systems(OnExit(Screen::Editor), despawn_grid);
     }
 }
 
 #[derive(Resource)]
-struct Map {
-    default_material: Handle<ColorMaterial>,
-    grass_material: Handle<ColorMaterial>,
-    mesh_handle: Handle<Mesh>,
+pub(crate) struct Map {
+    terrain_materials: HashMap<Terrain, Handle<ColorMaterial>>,
+    cursor_material: Handle<ColorMaterial>,
+    pub mesh_handle: Handle<Mesh>,
     cursor_mesh: Handle<Mesh>,
+    pub layout: HexLayout,
+}
+
+impl Map {
+    pub fn material(&self, terrain: Terrain) -> Handle<ColorMaterial> {
+        self.terrain_materials[&terrain].clone()
+    }
 }
 
 #[derive(Component)]
-struct MapHex;
+pub(crate) struct MapHex(pub Hex);
+
+/// Highlight around the hex under the mouse
+#[derive(Component)]
+struct MapCursor;
+
+/// Hex under the mouse, if it is on the map
+#[derive(Resource, Default)]
+pub(crate) struct HoveredHex {
+    pub hex: Option<Hex>,
+    /// world position of the mouse
+    pub position: Option<Vec2>,
+}
 
 /// Hex grid setup
 fn setup_grid(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    level: CurrentLevel,
 ) {
-    let layout = HexLayout::flat()
-        .with_hex_size(50.0)
-        .with_origin(vec2(0.0, 0.0));
+    let map = &level.get().map;
+    let layout = map.layout();
 
-    let default_material = materials.add(Color::Srgba(bevy::color::palettes::tailwind::GRAY_500));
-    let grass_material = materials.add(Color::Srgba(bevy::color::palettes::tailwind::GREEN_800));
+    let terrain_materials = Terrain::ALL
+        .into_iter()
+        .map(|terrain| (terrain, materials.add(terrain.color())))
+        .collect::<HashMap<_, _>>();
+    let cursor_material = materials.add(Color::WHITE);
 
     let mesh_handle = meshes.add(hexagonal_plane(&layout));
     let cursor_mesh = meshes.add(border_plane(&layout));
 
-    Hex::ZERO.range(15).for_each(|hex| {
+    map.hexes().for_each(|hex| {
         let pos = layout.hex_to_world_pos(hex);
         let _id = commands
             .spawn((
-                MapHex,
+                MapHex(hex),
                 Mesh2d(mesh_handle.clone()),
-                MeshMaterial2d(if (hex.x + hex.y) % 2 == 0 {
-                    default_material.clone_weak()
-                } else {
-                    grass_material.clone_weak()
-                }),
+                MeshMaterial2d(terrain_materials[&map.terrain(hex)].clone_weak()),
                 Transform::from_xyz(pos.x, pos.y, 0.0),
             ))
             .id();
     });
 
     commands.insert_resource(Map {
-        default_material,
-        grass_material,
+        terrain_materials,
+        cursor_material,
         mesh_handle,
         cursor_mesh,
+        layout,
     });
 }
 
+/// make wall hexes impassable and surround the map with colliders
+fn spawn_walls(
+    mut commands: Commands,
+    map: Res<Map>,
+    level: CurrentLevel,
+    hexes: Query<(Entity, &MapHex)>,
+) {
+    let level = level.get();
+    for (entity, MapHex(hex)) in &hexes {
+        if level.map.is_wall(*hex) {
+            commands
+                .entity(entity)
+                .insert((Name::new("Wall Hex"), hex_wall(&map.layout, *hex)));
+        }
+    }
+
+    // hexes off the map have no tile, only a collider where they border it
+    for hex in level.map.border() {
+        commands.spawn((
+            Name::new("Border Hex"),
+            MapHex(hex),
+            hex_wall(&map.layout, hex),
+            Transform::from_translation(map.layout.hex_to_world_pos(hex).extend(layers::MAP)),
+        ));
+    }
+}
+
+/// Resizing the window stalls the game, and the long frame that follows can carry
+/// things through walls. Put anything that ended up off the map back on it.
+fn keep_inside_arena(
+    level: CurrentLevel,
+    mut bodies: Query<&mut Transform, Or<(With<Enemy>, With<Player>)>>,
+) {
+    let map = &level.get().map;
+    let layout = map.layout();
+    for mut transform in &mut bodies {
+        let hex = layout.world_pos_to_hex(transform.translation.truncate());
+        if map.contains_hex(hex) && !map.is_wall(hex) {
+            continue;
+        }
+        let Some(nearest) = map
+            .hexes()
+            .filter(|h| !map.is_wall(*h))
+            .min_by_key(|h| h.unsigned_distance_to(hex))
+        else {
+            continue;
+        };
+        let z = transform.translation.z;
+        transform.translation = layout.hex_to_world_pos(nearest).extend(z);
+    }
+}
+
+/// static collider covering a hex
+fn hex_wall(layout: &HexLayout, hex: Hex) -> impl Bundle {
+    let center = layout.hex_to_world_pos(hex);
+    let corners = layout.hex_corners(hex).map(|corner| corner - center);
+    (
+        Wall,
+        Collider::convex_hull(corners.to_vec()).unwrap_or(Collider::circle(corners[0].length())),
+        RigidBody::Static,
+    )
+}
+
+fn spawn_cursor(mut commands: Commands, map: Res<Map>) {
+    commands.spawn((
+        Name::new("Map Cursor"),
+        MapCursor,
+        Mesh2d(map.cursor_mesh.clone()),
+        MeshMaterial2d(map.cursor_material.clone()),
+        Transform::from_xyz(0.0, 0.0, layers::MAP + 0.5),
+        Visibility::Hidden,
+    ));
+}
+
+fn move_cursor(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    map: Res<Map>,
+    level: CurrentLevel,
+    mut hovered: ResMut<HoveredHex>,
+    cursor: Single<(&mut Transform, &mut Visibility), With<MapCursor>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let position = window
+        .cursor_position()
+        .and_then(|p| camera.viewport_to_world_2d(camera_transform, p).ok());
+    let hex = position
+        .map(|p| map.layout.world_pos_to_hex(p))
+        .filter(|hex| level.get().map.contains_hex(*hex));
+
+    let (mut transform, mut visibility) = cursor.into_inner();
+    match hex {
+        Some(hex) => {
+            let pos = map.layout.hex_to_world_pos(hex);
+            transform.translation = pos.extend(transform.translation.z);
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+    if hovered.hex != hex || hovered.position != position {
+        *hovered = HoveredHex { hex, position };
+    }
+}
+
 /// compute mesh from layout
 fn hexagonal_plane(layout: &HexLayout) -> Mesh {
     let mesh_info = PlaneMeshBuilder::new(layout)
@@ -104,6 +243,6 @@ fn border_plane(layout: &HexLayout) -> Mesh {
     .with_inserted_indices(Indices::U16(mesh_info.indices))
 }
 
-fn despawn_grid(mut commands: Commands, hexes: Query<Entity, With<MapHex>>) {
+fn despawn_grid(mut commands: Commands, hexes: Query<Entity, Or<(With<MapHex>, With<MapCursor>)>>) {
     hexes.iter().for_each(|e| commands.entity(e).despawn());
 }
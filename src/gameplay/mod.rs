@@ -1,30 +1,68 @@
+pub(crate) mod arena;
+mod camera;
+pub(crate) mod containment;
+mod cutter;
+mod decoration;
+pub(crate) mod editor;
 mod enemy;
 mod enemy_ai;
+mod flow;
+mod goal;
+mod hazard;
+pub(crate) mod highscore;
+mod hud;
+mod ink;
 mod layers;
+pub(crate) mod level;
 mod map;
+pub(crate) mod mode;
 mod path;
+mod pathfinding;
 mod physics;
 mod player;
 mod rng_bag;
 mod score;
 mod state;
-use avian2d::prelude::{Collider, RigidBody};
-use bevy::{prelude::*, window::PrimaryWindow};
-
-use crate::screens::Screen;
+mod stats;
+mod terrain;
+mod territory;
+use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        camera::CameraPlugin,
+        containment::ContainmentPlugin,
+        cutter::CutterPlugin,
         enemy::EnemyPlugin,
         enemy_ai::EnemyAiPlugin,
         player::PlayerPlugin,
         path::PathPlugin,
         physics::PhysicsPlugin,
-        score::ScorePlugin,
         state::PlayingStatePlugin,
+        mode::ModePlugin,
+    ))
+    // level and map
+    .add_plugins((
+        arena::ArenaPlugin,
+        decoration::DecorationPlugin,
+        editor::EditorPlugin,
+        flow::FlowPlugin,
+        goal::GoalPlugin,
+        hazard::HazardPlugin,
+        level::LevelPlugin,
         map::MapPlugin,
+        pathfinding::PathfindingPlugin,
+        terrain::TerrainPlugin,
     ))
-    .add_systems(OnEnter(Screen::Gameplay), spawn_window_colliders);
+    // scoring and ui
+    .add_plugins((
+        highscore::HighScorePlugin,
+        hud::HudPlugin,
+        ink::InkPlugin,
+        score::ScorePlugin,
+        stats::StatsPlugin,
+        territory::TerritoryPlugin,
+    ));
 }
 
 #[derive(SystemSet, Hash, PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
@@ -32,35 +70,3 @@ struct DespawnSet;
 
 #[derive(Component)]
 struct Wall;
-
-fn spawn_window_colliders(mut commands: Commands, window: Single<&Window, With<PrimaryWindow>>) {
-    let size = window.size();
-    // top
-    commands.spawn((
-        Collider::half_space(Vec2::NEG_Y),
-        RigidBody::Kinematic,
-        Transform::from_xyz(0.0, size.y / 2., 0.0),
-        Wall,
-    ));
-    // bottom
-    commands.spawn((
-        Collider::half_space(Vec2::Y),
-        RigidBody::Kinematic,
-        Transform::from_xyz(0.0, -size.y / 2., 0.0),
-        Wall,
-    ));
-    // right
-    commands.spawn((
-        Collider::half_space(Vec2::NEG_X),
-        RigidBody::Kinematic,
-        Transform::from_xyz(size.x / 2., 0.0, 0.0),
-        Wall,
-    ));
-    // left
-    commands.spawn((
-        Collider::half_space(Vec2::X),
-        RigidBody::Kinematic,
-        Transform::from_xyz(-size.x / 2., 0.0, 0.0),
-        Wall,
-    ));
-}
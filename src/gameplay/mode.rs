@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        enemy::EnemyType,
+        path::ShapesCombined,
+        state::{Playing, RunOutcome, SurvivalTime},
+    },
+    screens::Screen,
+};
+
+pub(crate) struct ModePlugin;
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<RunSeed>()
+            .init_resource::<TargetProgress>()
+            .add_systems(OnEnter(Screen::Gameplay), reset_target_progress)
+            .add_systems(
+                Update,
+                (
+                    check_time_limit,
+                    check_target.run_if(resource_exists_and_equals(GameMode::Target)),
+                )
+                    .run_if(in_state(Screen::Gameplay))
+                    .run_if(in_state(Playing::Live)),
+            );
+    }
+}
+
+/// Rules the current game is played with
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub(crate) enum GameMode {
+    /// play until a white touches you
+    #[default]
+    Endless,
+    /// score as much as possible before the time runs out
+    TimeAttack,
+    /// make a number of a specific shape as fast as possible
+    Target,
+    /// claim as much of the map as possible with loops before the time runs out
+    Territory,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Endless,
+        GameMode::TimeAttack,
+        GameMode::Target,
+        GameMode::Territory,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Endless => "Endless",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Target => "Target",
+            GameMode::Territory => "Territory",
+        }
+    }
+
+    /// seconds the game lasts for
+    pub fn time_limit(&self) -> Option<f32> {
+        match self {
+            GameMode::TimeAttack | GameMode::Territory => Some(120.0),
+            _ => None,
+        }
+    }
+
+    /// shape type and count that wins the game
+    pub fn target(&self) -> Option<(EnemyType, usize)> {
+        match self {
+            GameMode::Target => Some((EnemyType::Purple, 5)),
+            _ => None,
+        }
+    }
+
+    /// seconds to complete the target in for a time bonus
+    pub fn par_time(&self) -> Option<f32> {
+        match self {
+            GameMode::Target => Some(180.0),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|m| m == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|m| m == self).unwrap_or_default();
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Seed the arena was generated from. `None` for unseeded games.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) struct RunSeed(pub Option<u64>);
+
+/// Number of target shapes made so far in target mode
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct TargetProgress(pub usize);
+
+fn reset_target_progress(mut progress: ResMut<TargetProgress>) {
+    **progress = 0;
+}
+
+fn check_time_limit(
+    mode: Res<GameMode>,
+    survival_time: Res<SurvivalTime>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_state: ResMut<NextState<Playing>>,
+) {
+    let Some(limit) = mode.time_limit() else {
+        return;
+    };
+
+    if survival_time.elapsed_secs() >= limit {
+        *outcome = RunOutcome::TimeUp;
+        next_state.set(Playing::Finished);
+    }
+}
+
+fn check_target(
+    mode: Res<GameMode>,
+    mut combined: EventReader<ShapesCombined>,
+    mut progress: ResMut<TargetProgress>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_state: ResMut<NextState<Playing>>,
+) {
+    let Some((target, count)) = mode.target() else {
+        return;
+    };
+
+    for event in combined.read() {
+        if event.new_type == target {
+            **progress += 1;
+        }
+    }
+
+    if **progress >= count {
+        *outcome = RunOutcome::TargetReached;
+        next_state.set(Playing::Finished);
+    }
+}
@@ -1,19 +1,28 @@
 use std::f32::consts::PI;
 
-use avian2d::prelude::{Collider, ColliderDisabled, LinearVelocity, RigidBody};
+use avian2d::prelude::{
+    Collider, ColliderDisabled, CollisionEventsEnabled, CollisionLayers, CollisionStarted,
+    ComputedMass, LinearVelocity, Mass, RigidBody, Sensor,
+};
 use bevy::{
     ecs::{component::HookContext, world::DeferredWorld},
     math::ops::exp,
-    platform::collections::HashMap,
+    platform::collections::{HashMap, HashSet},
     prelude::*,
 };
-use geo::{LineString, Point, Polygon, prelude::Contains};
+use geo::LineString;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     gameplay::{
         DespawnSet,
+        containment::{self, Containment, ContainmentSettings},
         enemy::{Enemy, EnemyHandles, EnemyType, SpawnEnemies},
+        ink::Ink,
+        level::CurrentLevel,
+        mode::GameMode,
+        physics::GameLayer,
     },
     screens::Screen,
 };
@@ -22,17 +31,39 @@ pub(crate) struct PathPlugin;
 impl Plugin for PathPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<LivePaths>()
+            .init_resource::<CombineSettings>()
+            .init_resource::<PathSettings>()
+            .add_event::<ShapesCombined>()
+            .add_event::<ShapeCrossedPath>()
+            .add_event::<CutPath>()
+            .add_event::<PathExtended>()
+            .add_event::<LoopClosed>()
+            .add_event::<ShapesExploded>()
             .add_systems(
                 FixedUpdate,
                 (
                     record_path,
+                    release_paths,
+                    cut_paths,
                     find_intersections,
                     check_areas,
+                    age_paths,
                     draw_path,
                     despawwn_old_paths,
                     animate_combining,
                 )
-                    .chain(),
+                    .chain()
+                    .in_set(PathSet),
+            )
+            .add_systems(
+                OnEnter(Screen::Gameplay),
+                (apply_mode_settings, apply_level_settings),
+            )
+            .add_systems(
+                Update,
+                (detect_crossings, flash_crossings)
+                    .chain()
+                    .run_if(in_state(Screen::Gameplay)),
             )
             .add_systems(
                 OnExit(Screen::Gameplay),
@@ -41,6 +72,10 @@ impl Plugin for PathPlugin {
     }
 }
 
+/// Systems that draw paths, close loops and combine the shapes inside
+#[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone)]
+pub(crate) struct PathSet;
+
 #[derive(Component, Default)]
 pub(crate) struct DrawPath {
     active: bool,
@@ -73,9 +108,8 @@ impl DrawPath {
 #[derive(Component)]
 #[component(on_insert = add_to_active_paths)]
 #[component(on_remove = remove_from_active_paths)]
-struct Path {
+pub(crate) struct Path {
     // Entity that drew this path
-    #[allow(dead_code)]
     pen: Entity,
     points: Vec<Vec2>,
     remainder: Option<Vec<Vec2>>,
@@ -91,6 +125,10 @@ fn remove_from_active_paths(mut world: DeferredWorld, context: HookContext) {
 }
 
 impl Path {
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
     fn to_line_string(&self) -> LineString<f32> {
         let coords = self
             .points
@@ -110,6 +148,86 @@ impl Path {
     }
 }
 
+/// How paths behave
+#[derive(Resource)]
+pub(crate) struct PathSettings {
+    pub physics: PathPhysics,
+    /// most paths that can exist at once, the oldest is removed first
+    pub max_paths: usize,
+    /// seconds a finished path lasts before it loses its colliders
+    pub lifetime: f32,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            physics: PathPhysics::default(),
+            max_paths: 4,
+            lifetime: 12.0,
+        }
+    }
+}
+
+impl PathSettings {
+    fn for_mode(mode: GameMode) -> Self {
+        match mode {
+            GameMode::Endless | GameMode::Target => Self::default(),
+            // fences don't stick around when racing the clock
+            GameMode::TimeAttack => Self {
+                max_paths: 3,
+                lifetime: 8.0,
+                ..default()
+            },
+            // loops paint the map as soon as they close, so old paths aren't needed
+            GameMode::Territory => Self {
+                max_paths: 2,
+                lifetime: 5.0,
+                ..default()
+            },
+        }
+    }
+}
+
+/// Seconds an expired path takes to fade out completely
+const FADE_OUT: f32 = 1.0;
+
+/// Seconds since the pen stopped drawing a path. Kept apart from `Path` so aging
+/// doesn't look like a change to the path's shape.
+#[derive(Component, Default)]
+struct PathAge(f32);
+
+/// Marker for a path that has outlived its lifetime and is fading out
+#[derive(Component)]
+struct ExpiredPath;
+
+/// What happens when a shape runs into a path
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) enum PathPhysics {
+    /// segments are solid and shapes bounce off them
+    #[default]
+    Fence,
+    /// shapes pass through and a `ShapeCrossedPath` is sent
+    Sensor,
+}
+
+/// Collider for one segment of a path, a child of the path
+#[derive(Component)]
+pub(crate) struct PathSegment;
+
+/// Segments only stop shapes while their path is being drawn, so the pen can't run
+/// into its own live path. See [`release_paths`].
+fn spawn_segment(parent: &mut ChildSpawnerCommands, from: Vec2, to: Vec2, physics: PathPhysics) {
+    let mut segment = parent.spawn((
+        PathSegment,
+        Collider::segment(from, to),
+        RigidBody::Kinematic,
+        CollisionLayers::new(GameLayer::Path, GameLayer::Default),
+    ));
+    if physics == PathPhysics::Sensor {
+        segment.insert((Sensor, CollisionEventsEnabled));
+    }
+}
+
 /// List of paths that currently exist. Used to remove the oldest path first
 #[derive(Resource, Default)]
 struct LivePaths(Vec<Entity>);
@@ -118,32 +236,89 @@ struct LivePaths(Vec<Entity>);
 #[derive(Component)]
 struct ClosedPath;
 
+/// Marker for a path whose pen has moved away from it, so the player runs into it
+#[derive(Component)]
+struct ReleasedPath;
+
+/// How far the pen has to get from a path it stopped drawing before it can run into it
+const RELEASE_DISTANCE: f32 = 20.0;
+
+/// Seconds a path and shape stay highlighted after the shape crosses the path
+const CROSSING_FLASH: f32 = 0.3;
+
+/// Highlight on a path or shape after a crossing
+#[derive(Component)]
+struct CrossingFlash(Timer);
+
+/// Sent when a pen adds a segment to its path
+#[derive(Event)]
+pub(crate) struct PathExtended {
+    pub length: f32,
+}
+
+/// Sent when a shape passes through a sensor path
+#[derive(Event)]
+pub(crate) struct ShapeCrossedPath {
+    pub shape: Entity,
+    pub path: Entity,
+}
+
+/// Cut a path where something ran into it
+#[derive(Event)]
+pub(crate) struct CutPath {
+    pub path: Entity,
+    /// segment that was hit
+    pub segment: Entity,
+}
+
+/// Sent when a path closes into a loop and the shapes inside have been found
+#[derive(Event)]
+pub(crate) struct LoopClosed {
+    /// entity that drew the loop
+    pub pen: Entity,
+    pub points: Vec<Vec2>,
+    pub area: f32,
+    /// number of shapes inside the loop
+    pub shapes: usize,
+}
+
 fn record_path(
     mut commands: Commands,
     mut pencils: Query<(Entity, &mut DrawPath, &Transform)>,
     mut paths: Query<&mut Path>,
+    mut extended: EventWriter<PathExtended>,
+    settings: Res<PathSettings>,
+    mut ink: ResMut<Ink>,
 ) -> Result<(), BevyError> {
     for (pencil, mut draw, t) in &mut pencils {
         if !draw.active {
             continue;
         }
+        if ink.is_empty() {
+            draw.deactivate();
+            continue;
+        }
 
         if let Some(path_entity) = draw.path
             && let Ok(mut path) = paths.get_mut(path_entity)
         {
             let new_point = t.translation.truncate();
-            let Some(last_point) = path.points.last() else {
+            let Some(last_point) = path.points.last().copied() else {
                 path.points.push(new_point);
                 continue;
             };
-            if new_point != *last_point {
+            if new_point != last_point {
+                // the ink can run out part way along the segment
+                let length = ink.spend(new_point.distance(last_point));
+                let new_point = last_point + (new_point - last_point).normalize() * length;
                 commands.entity(path_entity).with_children(|child| {
-                    child.spawn((
-                        Collider::segment(new_point, *last_point),
-                        RigidBody::Kinematic,
-                    ));
+                    spawn_segment(child, new_point, last_point, settings.physics);
                 });
+                extended.write(PathExtended { length });
                 path.points.push(new_point);
+                if ink.is_empty() {
+                    draw.deactivate();
+                }
             }
         } else {
             let points = vec![t.translation.truncate()];
@@ -154,6 +329,7 @@ fn record_path(
                         points,
                         remainder: None,
                     },
+                    PathAge::default(),
                     Transform::default(),
                 ))
                 .id();
@@ -164,27 +340,207 @@ fn record_path(
     Ok(())
 }
 
+/// Paths being drawn lose everything before the cut, other paths are removed
+fn cut_paths(
+    mut commands: Commands,
+    mut cuts: EventReader<CutPath>,
+    mut paths: Query<(&mut Path, &Children, Has<ClosedPath>)>,
+    pens: Query<&DrawPath>,
+) {
+    let mut removed = HashSet::<Entity>::default();
+    for cut in cuts.read() {
+        if removed.contains(&cut.path) {
+            continue;
+        }
+        let Ok((mut path, children, closed)) = paths.get_mut(cut.path) else {
+            continue;
+        };
+        let live = pens.iter().any(|draw| draw.is_active_path(cut.path));
+        if !live || closed || path.points.len() < 2 {
+            commands.entity(cut.path).despawn();
+            removed.insert(cut.path);
+            continue;
+        }
+        let Some(hit) = children.iter().position(|child| child == cut.segment) else {
+            continue;
+        };
+        let Some((stale, cut_points)) = cut_range(hit, children.len(), path.points.len()) else {
+            // an earlier cut this tick already removed the segment
+            continue;
+        };
+        path.points.drain(..cut_points);
+        path.remainder = None;
+
+        // segments are children in the order they were drawn
+        for segment in children.iter().take(hit + 1).skip(stale) {
+            commands.entity(segment).despawn();
+        }
+    }
+}
+
+/// segments cut earlier this tick stay children until the despawns are applied,
+/// so skip those and return how many of them there are and how many points the
+/// cut drops
+fn cut_range(hit: usize, segments: usize, points: usize) -> Option<(usize, usize)> {
+    let stale = (segments + 1).saturating_sub(points);
+    let cut = hit.checked_sub(stale)?;
+    Some((stale, cut + 1))
+}
+
+/// let the player run into a path once its pen is done with it and has moved away
+fn release_paths(
+    mut commands: Commands,
+    pens: Query<(&DrawPath, &Transform)>,
+    paths: Query<(Entity, &Path, &Children), Without<ReleasedPath>>,
+    mut segments: Query<&mut CollisionLayers, With<PathSegment>>,
+) {
+    for (path_entity, path, children) in &paths {
+        if let Ok((draw, transform)) = pens.get(path.pen) {
+            let position = transform.translation.truncate();
+            let near = path.points.windows(2).any(|segment| {
+                distance_to_segment_squared(position, segment[0], segment[1])
+                    < RELEASE_DISTANCE * RELEASE_DISTANCE
+            });
+            if draw.is_active_path(path_entity) || near {
+                continue;
+            }
+        }
+
+        for child in children.iter() {
+            if let Ok(mut layers) = segments.get_mut(child) {
+                layers.filters.add(GameLayer::Player);
+            }
+        }
+        commands.entity(path_entity).insert(ReleasedPath);
+    }
+}
+
+fn distance_to_segment_squared(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    (a + ab * t).distance_squared(point)
+}
+
+fn detect_crossings(
+    mut started: EventReader<CollisionStarted>,
+    segments: Query<&ChildOf, With<PathSegment>>,
+    shapes: Query<(), With<Enemy>>,
+    mut crossed: EventWriter<ShapeCrossedPath>,
+) {
+    // a shape can touch a few segments of the same path at once
+    let mut sent = HashSet::<(Entity, Entity)>::default();
+    for CollisionStarted(a, b) in started.read() {
+        let (segment, shape) = if segments.contains(*a) {
+            (*a, *b)
+        } else {
+            (*b, *a)
+        };
+        let Ok(child_of) = segments.get(segment) else {
+            continue;
+        };
+        if shapes.contains(shape) && sent.insert((shape, child_of.parent())) {
+            crossed.write(ShapeCrossedPath {
+                shape,
+                path: child_of.parent(),
+            });
+        }
+    }
+}
+
+/// highlight the shape and path of each crossing for a moment
+fn flash_crossings(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut crossed: EventReader<ShapeCrossedPath>,
+    mut flashes: Query<(Entity, &mut CrossingFlash, &Transform, Has<Enemy>)>,
+    mut gizmos: Gizmos,
+) {
+    for event in crossed.read() {
+        for entity in [event.shape, event.path] {
+            commands
+                .entity(entity)
+                .try_insert(CrossingFlash(Timer::from_seconds(
+                    CROSSING_FLASH,
+                    TimerMode::Once,
+                )));
+        }
+    }
+
+    for (entity, mut flash, transform, shape) in &mut flashes {
+        if flash.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<CrossingFlash>();
+        } else if shape {
+            gizmos.circle_2d(
+                transform.translation.truncate(),
+                30.0 * transform.scale.x,
+                Color::hsl(50., 1., 0.6),
+            );
+        }
+    }
+}
+
+/// the level picks whether paths are fences or sensors
+fn apply_level_settings(level: CurrentLevel, mut settings: ResMut<PathSettings>) {
+    settings.physics = level.get().path_physics;
+}
+
+/// only the cap and lifetime depend on the mode, the physics are set separately
+fn apply_mode_settings(mode: Res<GameMode>, mut settings: ResMut<PathSettings>) {
+    let for_mode = PathSettings::for_mode(*mode);
+    settings.max_paths = for_mode.max_paths;
+    settings.lifetime = for_mode.lifetime;
+}
+
+/// age finished paths, removing their colliders when they expire
+fn age_paths(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<PathSettings>,
+    pens: Query<&DrawPath>,
+    mut paths: Query<(Entity, &mut PathAge, Has<ExpiredPath>)>,
+) {
+    for (path_entity, mut age, expired) in &mut paths {
+        if pens.iter().any(|draw| draw.is_active_path(path_entity)) {
+            continue;
+        }
+        age.0 += time.delta_secs();
+        if age.0 >= settings.lifetime + FADE_OUT {
+            commands.entity(path_entity).despawn();
+        } else if age.0 >= settings.lifetime && !expired {
+            commands
+                .entity(path_entity)
+                .despawn_related::<Children>()
+                .insert(ExpiredPath);
+        }
+    }
+}
+
 fn draw_path(
-    paths: Query<(Entity, &Path)>,
+    paths: Query<(
+        Entity,
+        &Path,
+        &PathAge,
+        Has<ExpiredPath>,
+        Has<CrossingFlash>,
+    )>,
     mut gizmos: Gizmos,
     player: Single<&DrawPath>,
-    path_list: Res<LivePaths>,
+    settings: Res<PathSettings>,
 ) {
-    for (path_entity, path) in &paths {
+    for (path_entity, path, PathAge(age), expired, flash) in &paths {
+        let color = if flash {
+            Color::hsl(50., 1., 0.6)
+        } else if player.is_active_path(path_entity) {
+            Color::hsl(360., 1., 1.)
+        } else if expired {
+            // fade the rest of the way once the colliders are gone
+            let remaining = 1.0 - (age - settings.lifetime) / FADE_OUT;
+            Color::hsla(360., 0., 0.3, 0.5 * remaining.clamp(0.0, 1.0))
+        } else {
+            let remaining = 1.0 - age / settings.lifetime.max(f32::EPSILON);
+            Color::hsl(360., 0., 0.3 + 0.3 * remaining.clamp(0.0, 1.0))
+        };
         for (&point1, &point2) in path.points.iter().zip(path.points.iter().skip(1)) {
-            let color = if let Some(active_path) = player.path()
-                && path_entity == active_path
-            {
-                Color::hsl(360., 1., 1.)
-            } else {
-                let index = path_list
-                    .0
-                    .iter()
-                    .position(|e| *e == path_entity)
-                    .unwrap_or_default();
-                let shade = path_list.0.len() - index;
-                Color::hsl(360., 0., 0.6 - 0.075 * shade as f32)
-            };
             gizmos.line_2d(point1, point2, color);
         }
     }
@@ -223,25 +579,61 @@ fn find_intersections(
 
 fn check_areas(
     mut commands: Commands,
-    mut paths: Query<(Entity, &mut Path), (With<ClosedPath>, Changed<Path>)>,
-    enemies: Query<
-        (Entity, &Transform, &EnemyType, &LinearVelocity),
+    mut paths: Query<(Entity, &mut Path, Ref<ClosedPath>), Changed<Path>>,
+    mut enemies: Query<
+        (
+            Entity,
+            &Transform,
+            &EnemyType,
+            &mut LinearVelocity,
+            &Collider,
+            &ComputedMass,
+        ),
         (With<Enemy>, Without<ColliderDisabled>),
     >,
     mut pen: Single<&mut DrawPath>,
+    settings: Res<ContainmentSettings>,
+    mut loops: EventWriter<LoopClosed>,
 ) {
-    for (e, mut path) in &mut paths {
-        let polygon = Polygon::new(path.to_line_string(), vec![]);
+    for (e, mut path, closed) in &mut paths {
         let mut surrounded = Vec::new();
-        for (enemy_entity, transform, enemy_type, velocity) in &enemies {
-            if polygon.contains(&Point::new(
-                transform.translation.x,
-                transform.translation.y,
-            )) {
-                surrounded.push((enemy_entity, *enemy_type, *transform, velocity));
+        let mut push_out = Vec::new();
+        for (enemy_entity, transform, enemy_type, velocity, collider, mass) in &enemies {
+            let fraction = containment::overlap_fraction(&path.points, collider, transform);
+            match settings.classify(fraction) {
+                Containment::Inside => {
+                    surrounded.push((
+                        enemy_entity,
+                        *enemy_type,
+                        *transform,
+                        velocity.0,
+                        mass.value(),
+                    ));
+                }
+                Containment::PushOut => push_out.push(enemy_entity),
+                Containment::Outside => {}
             }
         }
 
+        // kick borderline shapes away from the middle of the loop
+        let center = containment::centroid(&path.points);
+        for enemy_entity in push_out {
+            let Ok((_, transform, _, mut velocity, ..)) = enemies.get_mut(enemy_entity) else {
+                continue;
+            };
+            let direction = (transform.translation.truncate() - center).normalize_or(Vec2::X);
+            velocity.0 = direction * settings.push_speed;
+        }
+
+        if closed.is_added() {
+            loops.write(LoopClosed {
+                pen: path.pen,
+                points: path.points.clone(),
+                area: containment::area(&path.points),
+                shapes: surrounded.len(),
+            });
+        }
+
         match surrounded.len() {
             0 => {
                 if pen.is_active_path(e) {
@@ -259,7 +651,7 @@ fn check_areas(
                 let mut combines = Vec::new();
                 let mut explode = Vec::new();
 
-                while let Some((check_entity, typ, _t, v)) = surrounded.pop() {
+                while let Some((check_entity, typ, t, v, mass)) = surrounded.pop() {
                     let mut entities = vec![check_entity];
 
                     // check for complements
@@ -267,13 +659,15 @@ fn check_areas(
                         .iter()
                         .position(|(_, other_type, ..)| typ.complement() == *other_type);
                     if let Some(pos) = complement_pos {
-                        let (comp_e, comp_typ, _comp_t, comp_v) = surrounded.remove(pos);
+                        let (comp_e, comp_typ, comp_t, comp_v, comp_mass) = surrounded.remove(pos);
                         entities.push(comp_e);
 
                         combines.push(Combine {
                             entities,
                             new_type: typ.pair_combine(comp_typ).unwrap(),
-                            velocity: (**v + **comp_v) / 2.,
+                            velocity: (v + comp_v) / 2.,
+                            mass: mass + comp_mass,
+                            area: scaled_area(typ, &t) + scaled_area(comp_typ, &comp_t),
                         });
                         continue;
                     }
@@ -304,7 +698,9 @@ fn check_areas(
                         combines.push(Combine {
                             entities,
                             new_type,
-                            velocity: (**v + **result1.3) / 2.0,
+                            velocity: (v + result1.3) / 2.0,
+                            mass: mass + result1.4,
+                            area: scaled_area(typ, &t) + scaled_area(result1.1, &result1.2),
                         });
                         continue;
                     };
@@ -315,7 +711,11 @@ fn check_areas(
                     combines.push(Combine {
                         entities,
                         new_type: EnemyType::White,
-                        velocity: (**v + **result1.3 + **result2.3) / 3.0,
+                        velocity: (v + result1.3 + result2.3) / 3.0,
+                        mass: mass + result1.4 + result2.4,
+                        area: scaled_area(typ, &t)
+                            + scaled_area(result1.1, &result1.2)
+                            + scaled_area(result2.1, &result2.2),
                     });
                 }
 
@@ -324,11 +724,54 @@ fn check_areas(
         }
     }
 }
+
+/// Sent when a group of shapes in a loop combines into a new shape
+#[derive(Event)]
+pub(crate) struct ShapesCombined {
+    pub new_type: EnemyType,
+    /// number of combines that happened in the same loop
+    pub loop_combines: usize,
+}
+
+/// Sent when shapes in a loop fail to combine and get ejected
+#[derive(Event)]
+pub(crate) struct ShapesExploded {
+    pub count: usize,
+}
+
+/// area of the enemy including any scaling it inherited from combining
+fn scaled_area(typ: EnemyType, transform: &Transform) -> f32 {
+    typ.area() * transform.scale.x * transform.scale.y
+}
+
 /// Combine these entites into one enemy
 struct Combine {
     entities: Vec<Entity>,
     new_type: EnemyType,
     velocity: Vec2,
+    /// summed mass of the combined entities
+    mass: f32,
+    /// summed area of the combined entities
+    area: f32,
+}
+
+/// How shapes created by combining inherit from their parts
+#[derive(Resource)]
+pub(crate) struct CombineSettings {
+    /// scale the new shape so its area matches the area of its parts
+    pub inherit_size: bool,
+    pub min_scale: f32,
+    pub max_scale: f32,
+}
+
+impl Default for CombineSettings {
+    fn default() -> Self {
+        Self {
+            inherit_size: false,
+            min_scale: 0.5,
+            max_scale: 2.5,
+        }
+    }
 }
 
 #[derive(Component)]
@@ -422,8 +865,11 @@ fn animate_combining(
     mut animations: Query<(Entity, &mut AnimateCombining)>,
     mut enemies: Query<(&mut Transform, &mut LinearVelocity), With<Enemy>>,
     mut spawn_enemies: EventWriter<SpawnEnemies>,
+    mut combined: EventWriter<ShapesCombined>,
+    mut exploded: EventWriter<ShapesExploded>,
     paths: Query<Entity, With<Path>>,
     handles: Res<EnemyHandles>,
+    combine_settings: Res<CombineSettings>,
     mut animation_timeout: Local<TimeoutTimer>,
 ) {
     const RADIUS: f32 = 10.0;
@@ -479,7 +925,9 @@ fn animate_combining(
                 ..
             } => {
                 for (entity, target) in target_positions.iter() {
-                    let mut transform = enemies.get_mut(*entity).map(|(t, ..)| t).unwrap();
+                    let Ok((mut transform, _)) = enemies.get_mut(*entity) else {
+                        continue;
+                    };
                     transform.translation = transform
                         .translation
                         .lerp(target.extend(0.0), 1. - exp(-10. * time.delta_secs()));
@@ -508,6 +956,9 @@ fn animate_combining(
                     for e in &paths {
                         commands.entity(e).despawn();
                     }
+                    exploded.write(ShapesExploded {
+                        count: explode.len(),
+                    });
                 }
 
                 anim.transition_to_done();
@@ -521,15 +972,28 @@ fn animate_combining(
                         commands.entity(*e).despawn();
                     }
 
-                    combine.new_type.spawn(
+                    let scale = if combine_settings.inherit_size {
+                        (combine.area / combine.new_type.area())
+                            .sqrt()
+                            .clamp(combine_settings.min_scale, combine_settings.max_scale)
+                    } else {
+                        1.0
+                    };
+                    let id = combine.new_type.spawn(
                         &mut commands,
-                        Transform::from_translation(center.extend(0.0)),
+                        Transform::from_translation(center.extend(0.0))
+                            .with_scale(Vec3::new(scale, scale, 1.0)),
                         LinearVelocity(combine.velocity),
                         &handles,
                     );
+                    commands.entity(id).insert(Mass(combine.mass));
+                    combined.write(ShapesCombined {
+                        new_type: combine.new_type,
+                        loop_combines: combines.len(),
+                    });
 
                     if combine.new_type == EnemyType::White {
-                        spawn_enemies.write(SpawnEnemies);
+                        spawn_enemies.write(SpawnEnemies::default());
                     }
                 }
 
@@ -540,8 +1004,12 @@ fn animate_combining(
     }
 }
 
-fn despawwn_old_paths(mut commands: Commands, live_paths: Res<LivePaths>) {
-    if live_paths.0.len() > 4 {
+fn despawwn_old_paths(
+    mut commands: Commands,
+    live_paths: Res<LivePaths>,
+    settings: Res<PathSettings>,
+) {
+    if live_paths.0.len() > settings.max_paths {
         commands.entity(live_paths.0[0]).despawn();
     }
 }
@@ -551,3 +1019,28 @@ fn despawn_all_paths(mut commands: Commands, paths: Query<Entity, With<Path>>) {
         commands.entity(e).despawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cut_drops_points_up_to_the_hit_segment() {
+        // 5 points joined by 4 segments, the third segment is hit
+        assert_eq!(cut_range(2, 4, 5), Some((0, 3)));
+    }
+
+    #[test]
+    fn second_cut_in_a_tick_skips_stale_segments() {
+        // the first cut dropped 2 points but its 2 segments are still children
+        assert_eq!(cut_range(3, 4, 3), Some((2, 2)));
+        // hitting one of the segments the first cut already removed
+        assert_eq!(cut_range(1, 4, 3), None);
+    }
+
+    #[test]
+    fn cut_keeps_the_last_point() {
+        let (_, drop) = cut_range(3, 4, 5).unwrap();
+        assert_eq!(drop, 4);
+    }
+}
@@ -0,0 +1,197 @@
+//! Paths around walls on the hex map.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+};
+use hexx::{Hex, HexLayout};
+
+use crate::{gameplay::level::CurrentLevel, screens::Screen};
+
+pub(crate) struct PathfindingPlugin;
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .add_systems(OnEnter(Screen::Gameplay), build_nav_grid);
+    }
+}
+
+/// Hexes of the current level that can be moved through
+#[derive(Resource, Default)]
+pub(crate) struct NavGrid {
+    layout: HexLayout,
+    hex_size: f32,
+    open: HashSet<Hex>,
+}
+
+impl NavGrid {
+    pub fn hex(&self, position: Vec2) -> Hex {
+        self.layout.world_pos_to_hex(position)
+    }
+
+    pub fn position(&self, hex: Hex) -> Vec2 {
+        self.layout.hex_to_world_pos(hex)
+    }
+
+    pub fn is_open(&self, hex: Hex) -> bool {
+        self.open.contains(&hex)
+    }
+
+    /// whether a straight line between two hexes only crosses open hexes
+    pub fn clear_line(&self, from: Hex, to: Hex) -> bool {
+        from.line_to(to).all(|hex| self.is_open(hex))
+    }
+
+    /// shortest path of open hexes from `start` to `goal`, both included
+    pub fn path(&self, start: Hex, goal: Hex) -> Option<Vec<Hex>> {
+        if !self.is_open(start) || !self.is_open(goal) {
+            return None;
+        }
+
+        let mut came_from = HashMap::<Hex, Hex>::default();
+        let mut cost = HashMap::<Hex, u32>::from_iter([(start, 0)]);
+        // hexes are stored as arrays so the heap has a total order
+        let mut frontier = BinaryHeap::from([Reverse((
+            start.unsigned_distance_to(goal),
+            start.to_array(),
+        ))]);
+
+        while let Some(Reverse((_, current))) = frontier.pop() {
+            let current = Hex::from(current);
+            if current == goal {
+                let mut path = vec![goal];
+                let mut hex = goal;
+                while let Some(previous) = came_from.get(&hex) {
+                    path.push(*previous);
+                    hex = *previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let next_cost = cost[&current] + 1;
+            for neighbor in current.all_neighbors() {
+                if !self.is_open(neighbor) || cost.get(&neighbor).is_some_and(|c| *c <= next_cost) {
+                    continue;
+                }
+                cost.insert(neighbor, next_cost);
+                came_from.insert(neighbor, current);
+                let estimate = next_cost + neighbor.unsigned_distance_to(goal);
+                frontier.push(Reverse((estimate, neighbor.to_array())));
+            }
+        }
+        None
+    }
+
+    /// direction to move away from `threat`, turning along walls instead of into them
+    pub fn away_from(&self, position: Vec2, threat: Vec2) -> Vec2 {
+        let away = (position - threat).normalize_or_zero();
+        let hex = self.hex(position);
+        let ahead = self.hex(position + away * self.hex_size);
+        if ahead == hex || self.is_open(ahead) {
+            return away;
+        }
+        hex.all_neighbors()
+            .into_iter()
+            .filter(|neighbor| self.is_open(*neighbor))
+            .map(|neighbor| self.position(neighbor))
+            .max_by(|a, b| {
+                a.distance_squared(threat)
+                    .total_cmp(&b.distance_squared(threat))
+            })
+            .map_or(away, |best| (best - position).normalize_or_zero())
+    }
+}
+
+/// Next hex on the way to a target, kept until either end moves to another hex
+#[derive(Component, Default)]
+pub(crate) struct Route {
+    /// start and goal hex the route was found for
+    ends: Option<(Hex, Hex)>,
+    next: Option<Hex>,
+}
+
+impl Route {
+    /// position to steer towards to get to `target`, straight at it when nothing is in the way
+    pub fn waypoint(&mut self, nav: &NavGrid, position: Vec2, target: Vec2) -> Vec2 {
+        let ends = (nav.hex(position), nav.hex(target));
+        if nav.clear_line(ends.0, ends.1) {
+            return target;
+        }
+        if self.ends != Some(ends) {
+            self.ends = Some(ends);
+            self.next = nav
+                .path(ends.0, ends.1)
+                .and_then(|path| path.get(1).copied());
+        }
+        self.next.map_or(target, |hex| nav.position(hex))
+    }
+}
+
+fn build_nav_grid(mut commands: Commands, level: CurrentLevel) {
+    let map = &level.get().map;
+    commands.insert_resource(NavGrid {
+        layout: map.layout(),
+        hex_size: map.hex_size,
+        open: map.hexes().filter(|hex| !map.is_wall(*hex)).collect(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// hex map of radius 4 with the given hexes walled off
+    fn grid(walls: impl IntoIterator<Item = Hex>) -> NavGrid {
+        let walls = walls.into_iter().collect::<HashSet<_>>();
+        NavGrid {
+            open: Hex::ZERO
+                .range(4)
+                .filter(|hex| !walls.contains(hex))
+                .collect(),
+            ..default()
+        }
+    }
+
+    /// every step of the path is an open neighbor of the one before
+    fn assert_connected(nav: &NavGrid, path: &[Hex]) {
+        assert!(path.iter().all(|hex| nav.is_open(*hex)));
+        for step in path.windows(2) {
+            assert_eq!(step[0].unsigned_distance_to(step[1]), 1);
+        }
+    }
+
+    #[test]
+    fn straight_route() {
+        let nav = grid([]);
+        let (start, goal) = (Hex::new(-3, 0), Hex::new(3, 0));
+        let path = nav.path(start, goal).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path.len(), 7);
+        assert_connected(&nav, &path);
+    }
+
+    #[test]
+    fn routes_around_a_wall() {
+        // a wall across the map with a gap at the top
+        let nav = grid((-4..=3).map(|y| Hex::new(0, y)));
+        let (start, goal) = (Hex::new(-3, 0), Hex::new(3, 0));
+        let path = nav.path(start, goal).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(path.contains(&Hex::new(0, 4)));
+        assert!(path.len() > 7);
+        assert_connected(&nav, &path);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let nav = grid((-4..=4).map(|y| Hex::new(0, y)));
+        assert_eq!(nav.path(Hex::new(-3, 0), Hex::new(3, 0)), None);
+        // walled in goal
+        assert_eq!(nav.path(Hex::new(-3, 0), Hex::new(0, 0)), None);
+    }
+}
@@ -1,3 +1,4 @@
+use avian2d::prelude::PhysicsLayer;
 use bevy::prelude::*;
 
 /// Currently only controls player. other physics are implemented using avian
@@ -9,6 +10,17 @@ impl Plugin for PhysicsPlugin {
     }
 }
 
+/// Collision layers for avian
+#[derive(PhysicsLayer, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) enum GameLayer {
+    /// shapes and walls
+    #[default]
+    Default,
+    Player,
+    /// segments of drawn paths, only shapes run into these
+    Path,
+}
+
 #[derive(Component, Deref, DerefMut, Default)]
 pub(crate) struct Acceleration(pub(crate) Vec2);
 
@@ -1,7 +1,8 @@
 use std::f32::consts::PI;
 
 use avian2d::prelude::{
-    CoefficientCombine, Collider, Collisions, Friction, Restitution, RigidBody,
+    CoefficientCombine, Collider, CollisionLayers, Collisions, Friction, LayerMask, Restitution,
+    RigidBody, Sensor,
 };
 use bevy::{prelude::*, window::PrimaryWindow};
 
@@ -9,9 +10,11 @@ use crate::{
     gameplay::{
         DespawnSet, Wall,
         enemy::EnemyType,
-        path::DrawPath,
-        physics::{Acceleration, MaxSpeed, Velocity},
+        path::{DrawPath, PathSegment},
+        physics::{Acceleration, GameLayer, MaxSpeed, Velocity},
         state::Playing,
+        terrain::TerrainMap,
+        territory::PenColor,
     },
     screens::Screen,
 };
@@ -60,34 +63,34 @@ fn spawn_player(
         Velocity::default(),
         Acceleration::default(),
         DrawPath::default(),
+        PenColor::default(),
         RigidBody::Kinematic,
         Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
         Collider::triangle(triangle_points[0], triangle_points[1], triangle_points[2]),
         Restitution::new(0.8),
+        // paths only collide with the player once it has finished drawing them
+        CollisionLayers::new(GameLayer::Player, LayerMask::ALL),
         Transform::from_xyz(0.0, 0.0, crate::gameplay::layers::ON_GROUND),
     ));
 }
 
 fn point_player(
     // TODO: can we get the off window coordinates?
-    mut cursor_pos: EventReader<CursorMoved>,
-    camera: Query<&Transform, (With<Camera>, Without<Player>)>,
     window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
     mut player_query: Query<&mut Transform, With<Player>>,
 ) -> Result<(), BevyError> {
-    let window = window.single()?;
-    let Some(position) = cursor_pos.read().last() else {
+    // read the cursor every frame, the camera can move while the mouse stays still
+    let Some(position) = window.single()?.cursor_position() else {
         return Ok(());
     };
 
-    let Ok(transform) = camera.single() else {
+    let Ok((camera, camera_transform)) = camera.single() else {
         return Ok(());
     };
 
-    // convert cursor_pos into world coordinates
-    let size = Vec2::new(window.width(), window.height());
-    let center_offset = (position.position - size / 2.0) * Vec2::new(1.0, -1.0);
-    let world_cursor = center_offset + transform.translation.truncate();
+    // convert cursor position into world coordinates
+    let world_cursor = camera.viewport_to_world_2d(camera_transform, position)?;
 
     let mut player_transform = player_query.single_mut()?;
 
@@ -102,6 +105,7 @@ fn accelerate_player(
     key: Res<ButtonInput<KeyCode>>,
     mouse: Res<ButtonInput<MouseButton>>,
     player: Single<(&Transform, &Velocity, &mut Acceleration), With<Player>>,
+    terrain: Res<TerrainMap>,
 ) {
     // TODO: move these to a config file
     const FRICTION_TRANSVERSE: f32 = 1000.0;
@@ -111,29 +115,39 @@ fn accelerate_player(
     const FORWARD_ACCEL_REVERSE: f32 = 800.0;
 
     let (t, v, mut a) = player.into_inner();
+    let terrain = terrain.at(t.translation.truncate());
+    let friction = terrain.friction();
+    let traction = terrain.traction();
 
     // calculate forward acceleration
     let (_, _, angle) = t.rotation.to_euler(EulerRot::XYZ);
     let forward = Vec2::from_angle(angle + PI / 2.);
     let v_forward = v.dot(forward);
-    let a_forward = if key.pressed(KeyCode::KeyW) || mouse.pressed(MouseButton::Left) {
+    let accelerating = key.pressed(KeyCode::KeyW) || mouse.pressed(MouseButton::Left);
+    let a_forward = if accelerating {
         if v_forward >= 0.0 {
-            FORWARD_ACCEL
+            FORWARD_ACCEL * traction
         } else {
-            FORWARD_ACCEL_REVERSE
+            FORWARD_ACCEL_REVERSE * traction
         }
     } else if key.pressed(KeyCode::KeyS) || mouse.pressed(MouseButton::Middle) {
         if v_forward > 0.0 {
-            -FRICTION_BRAKE
+            -FRICTION_BRAKE * traction
         } else if v_forward < 0.0 {
-            FRICTION_BRAKE
+            FRICTION_BRAKE * traction
         } else {
             0.0
         }
     } else if v_forward < 0.0 {
-        FRICTION_NEUTRAL
+        FRICTION_NEUTRAL * friction
     } else if v_forward > 0.0 {
-        -FRICTION_NEUTRAL
+        -FRICTION_NEUTRAL * friction
+    } else {
+        0.0
+    };
+    // boost only adds to speeding up in the direction of travel, not to starting, braking or reversing
+    let boost = if accelerating && v_forward > f32::EPSILON {
+        terrain.boost()
     } else {
         0.0
     };
@@ -142,14 +156,14 @@ fn accelerate_player(
     let transverse = forward.perp();
     let v_transverse = v.dot(transverse);
     let a_transverse = if v_transverse > 0.0 {
-        -FRICTION_TRANSVERSE
+        -FRICTION_TRANSVERSE * friction
     } else if v_transverse < 0.0 {
-        FRICTION_TRANSVERSE
+        FRICTION_TRANSVERSE * friction
     } else {
         0.0
     };
 
-    **a = forward.normalize() * a_forward + transverse.normalize() * a_transverse;
+    **a = forward.normalize() * (a_forward + boost) + transverse.normalize() * a_transverse;
 }
 
 fn control_drawing(
@@ -173,16 +187,19 @@ fn control_drawing(
 fn handle_player_collisions(
     player: Single<(Entity, &mut Velocity, &mut Transform), With<Player>>,
     walls: Query<(), With<Wall>>,
+    fences: Query<(), (With<PathSegment>, Without<Sensor>)>,
     enemies: Query<&EnemyType>,
     collisions: Collisions,
     mut next_state: ResMut<NextState<Playing>>,
 ) {
     let (player, mut player_v, mut player_t) = player.into_inner();
     for contact_pair in collisions.collisions_with(player) {
-        // walls
-        if walls.contains(contact_pair.collider1) {
-            let normal = contact_pair.manifolds[0].normal;
-            let colliion_perp = contact_pair.manifolds[0].normal.perp();
+        // walls and finished fences
+        if (walls.contains(contact_pair.collider1) || fences.contains(contact_pair.collider1))
+            && let Some(manifold) = contact_pair.manifolds.first()
+        {
+            let normal = manifold.normal;
+            let colliion_perp = manifold.normal.perp();
             // zero velocity in direction of impulse
             player_v.0 = player_v.0.dot(colliion_perp) * colliion_perp;
             // make sure player is outside of wall
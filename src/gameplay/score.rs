@@ -1,7 +1,18 @@
 use bevy::prelude::*;
 
 use crate::{
-    gameplay::{DespawnSet, enemy::EnemyType},
+    AppSystems, PausableSystems,
+    gameplay::{
+        DespawnSet,
+        editor::{self, TestPlay},
+        enemy::EnemyType,
+        highscore::{self, HighScores, ScoreKey},
+        level::CurrentLevel,
+        mode::{GameMode, RunSeed},
+        path::{ShapesCombined, ShapesExploded},
+        state::{Playing, RunOutcome, SurvivalTime},
+        stats::{self, RunStats},
+    },
     screens::Screen,
     theme::{palette::HEADER_TEXT, widget},
 };
@@ -10,11 +21,188 @@ pub(crate) struct ScorePlugin;
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Score>()
+            .init_resource::<ScoreRules>()
+            .init_resource::<LiveScore>()
+            .add_systems(
+                OnEnter(Screen::Gameplay),
+                (reset_live_score, apply_mode_rules),
+            )
+            .add_systems(OnEnter(Playing::Finished), score_completion)
+            .add_systems(
+                Update,
+                (
+                    tick_combo.in_set(AppSystems::TickTimers),
+                    (score_combines, score_explosions)
+                        .chain()
+                        .in_set(AppSystems::Update),
+                )
+                    .in_set(PausableSystems)
+                    .run_if(in_state(Screen::Gameplay)),
+            )
             .add_systems(OnExit(Screen::Gameplay), record_score.before(DespawnSet))
             .add_systems(OnEnter(Screen::Score), spawn_score);
     }
 }
 
+/// Points awarded while playing
+#[derive(Resource)]
+pub(crate) struct ScoreRules {
+    /// points for making a secondary color
+    pub secondary: usize,
+    /// points for making a white
+    pub white: usize,
+    /// points for each shape that gets ejected from a loop
+    pub exploded: usize,
+    /// seconds after a combine that another combine continues the combo
+    pub combo_window: f32,
+    /// extra multiplier for each combine in the current combo
+    pub combo_step: f32,
+    /// extra multiplier for each additional combine in the same loop
+    pub loop_step: f32,
+    /// points for reaching the game mode's target
+    pub completion_bonus: usize,
+    /// points for each second the target is reached under par time
+    pub time_bonus: usize,
+    /// points for each uncontested hex claimed when the game ends
+    pub claimed_hex: usize,
+}
+
+impl Default for ScoreRules {
+    fn default() -> Self {
+        Self {
+            secondary: 100,
+            white: 300,
+            exploded: 10,
+            combo_window: 3.0,
+            combo_step: 0.5,
+            loop_step: 1.0,
+            completion_bonus: 0,
+            time_bonus: 0,
+            claimed_hex: 0,
+        }
+    }
+}
+
+impl ScoreRules {
+    fn for_mode(mode: GameMode) -> Self {
+        match mode {
+            GameMode::Endless => Self::default(),
+            // combos are worth more when racing the clock
+            GameMode::TimeAttack => Self {
+                combo_window: 2.0,
+                combo_step: 1.0,
+                ..default()
+            },
+            // score mostly comes from finishing quickly
+            GameMode::Target => Self {
+                secondary: 50,
+                white: 100,
+                exploded: 0,
+                completion_bonus: 1000,
+                time_bonus: 20,
+                ..default()
+            },
+            // score mostly comes from the territory held at the end
+            GameMode::Territory => Self {
+                secondary: 20,
+                white: 50,
+                exploded: 0,
+                claimed_hex: 25,
+                ..default()
+            },
+        }
+    }
+}
+
+fn apply_mode_rules(mut commands: Commands, mode: Res<GameMode>) {
+    commands.insert_resource(ScoreRules::for_mode(*mode));
+}
+
+fn score_completion(
+    rules: Res<ScoreRules>,
+    outcome: Res<RunOutcome>,
+    mode: Res<GameMode>,
+    level: CurrentLevel,
+    survival_time: Res<SurvivalTime>,
+    mut score: ResMut<LiveScore>,
+) {
+    if *outcome != RunOutcome::TargetReached {
+        return;
+    }
+
+    let under_par = level
+        .get()
+        .par_time
+        .or(mode.par_time())
+        .map(|par| (par - survival_time.elapsed_secs()).max(0.0))
+        .unwrap_or_default();
+    score.points += rules.completion_bonus + rules.time_bonus * under_par as usize;
+}
+
+/// Running score for the current game
+#[derive(Resource, Default)]
+pub(crate) struct LiveScore {
+    pub points: usize,
+    /// number of combines in the current chain
+    pub combo: usize,
+    pub best_combo: usize,
+    combo_timer: Timer,
+}
+
+impl LiveScore {
+    fn combo_multiplier(&self, rules: &ScoreRules) -> f32 {
+        // the first combine in a chain is not a combo
+        1.0 + rules.combo_step * self.combo.saturating_sub(1) as f32
+    }
+}
+
+fn reset_live_score(mut commands: Commands) {
+    commands.insert_resource(LiveScore::default());
+}
+
+fn tick_combo(time: Res<Time>, mut score: ResMut<LiveScore>) {
+    if score.combo == 0 {
+        return;
+    }
+
+    // ticking isn't a change anything shows, only the combo ending is
+    let timer = &mut score.bypass_change_detection().combo_timer;
+    if timer.tick(time.delta()).finished() {
+        score.combo = 0;
+    }
+}
+
+fn score_combines(
+    mut combined: EventReader<ShapesCombined>,
+    rules: Res<ScoreRules>,
+    mut score: ResMut<LiveScore>,
+) {
+    for event in combined.read() {
+        score.combo += 1;
+        score.best_combo = score.best_combo.max(score.combo);
+        score.combo_timer = Timer::from_seconds(rules.combo_window, TimerMode::Once);
+
+        let base = if event.new_type == EnemyType::White {
+            rules.white
+        } else {
+            rules.secondary
+        };
+        let loop_multiplier = 1.0 + rules.loop_step * event.loop_combines.saturating_sub(1) as f32;
+        let multiplier = score.combo_multiplier(&rules) * loop_multiplier;
+        score.points += (base as f32 * multiplier).round() as usize;
+    }
+}
+
+fn score_explosions(
+    mut exploded: EventReader<ShapesExploded>,
+    rules: Res<ScoreRules>,
+    mut score: ResMut<LiveScore>,
+) {
+    for event in exploded.read() {
+        score.points += rules.exploded * event.count;
+    }
+}
+
 #[derive(Resource, Default)]
 struct Score {
     blue: usize,
@@ -44,23 +232,69 @@ fn record_score(mut commands: Commands, enemies: Query<&EnemyType>) {
     commands.insert_resource(score);
 }
 
-fn spawn_score(mut commands: Commands, score: Res<Score>) {
-    commands.spawn((
-        widget::ui_root("Score"),
-        GlobalZIndex(2),
-        StateScoped(Screen::Score),
-        children![
-            widget::header("Score"),
-            score_text("White", score.white),
-            score_text("Red", score.red),
-            score_text("Green", score.green),
-            score_text("Blue", score.blue),
-            score_text("Purple", score.purple),
-            score_text("Yellow", score.yellow),
-            score_text("Cyan", score.cyan),
-            widget::button("Quit to title", quit_to_title),
-        ],
-    ));
+fn spawn_score(
+    mut commands: Commands,
+    score: Res<Score>,
+    live_score: Res<LiveScore>,
+    stats: Res<RunStats>,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+    seed: Res<RunSeed>,
+    outcome: Res<RunOutcome>,
+    test_play: Option<Res<TestPlay>>,
+) {
+    let qualifies = high_scores.qualifies(ScoreKey::new(*mode, *seed), live_score.points);
+    commands
+        .spawn((
+            widget::ui_root("Score"),
+            GlobalZIndex(2),
+            StateScoped(Screen::Score),
+            children![
+                widget::header(outcome.title()),
+                widget::label(mode.name()),
+                (
+                    Name::new("Score Columns"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(60.),
+                        ..default()
+                    },
+                    children![
+                        (
+                            Name::new("Shape Counts"),
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(8.),
+                                ..default()
+                            },
+                            children![
+                                score_text("Points", live_score.points),
+                                score_text("Best combo", live_score.best_combo),
+                                score_text("White", score.white),
+                                score_text("Red", score.red),
+                                score_text("Green", score.green),
+                                score_text("Blue", score.blue),
+                                score_text("Purple", score.purple),
+                                score_text("Yellow", score.yellow),
+                                score_text("Cyan", score.cyan),
+                            ],
+                        ),
+                        stats::stats_panel(&stats),
+                    ],
+                ),
+            ],
+        ))
+        .with_children(|parent| {
+            if qualifies {
+                parent.spawn(highscore::name_entry());
+            }
+            #[cfg(not(target_family = "wasm"))]
+            parent.spawn(widget::button("Export stats", export_stats));
+            if test_play.is_some() {
+                parent.spawn(widget::button("Back to editor", editor::back_to_editor));
+            }
+            parent.spawn(widget::button("Quit to title", quit_to_title));
+        });
 }
 
 /// A simple header label. Bigger than [`label`].
@@ -93,6 +327,11 @@ pub fn score_text(text: impl Into<String>, score: usize) -> impl Bundle {
     )
 }
 
+#[cfg(not(target_family = "wasm"))]
+fn export_stats(_: Trigger<Pointer<Click>>, stats: Res<RunStats>) {
+    stats::export(&stats);
+}
+
 fn quit_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
@@ -1,13 +1,30 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, time::Stopwatch};
 
-use crate::screens::Screen;
+use crate::{AppSystems, PausableSystems, screens::Screen};
 
 pub struct PlayingStatePlugin;
 impl Plugin for PlayingStatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<Playing>()
-            .add_systems(OnEnter(Screen::Gameplay), init_state)
-            .add_systems(Update, transition_to_dead.run_if(in_state(Playing::Dying)))
+            .init_resource::<SurvivalTime>()
+            .init_resource::<RunOutcome>()
+            .add_systems(
+                OnEnter(Screen::Gameplay),
+                (init_state, reset_survival_time, reset_outcome),
+            )
+            .add_systems(
+                Update,
+                tick_survival_time
+                    .in_set(AppSystems::TickTimers)
+                    .in_set(PausableSystems)
+                    .run_if(in_state(Screen::Gameplay))
+                    .run_if(in_state(Playing::Live)),
+            )
+            .add_systems(OnEnter(Playing::Dying), record_death)
+            .add_systems(
+                Update,
+                transition_to_dead.run_if(in_state(Playing::Dying).or(in_state(Playing::Finished))),
+            )
             .add_systems(OnEnter(Playing::Dead), transition_to_score_screen);
     }
 }
@@ -18,13 +35,54 @@ pub enum Playing {
     #[default]
     Live,
     Dying,
+    /// the game mode's goal or time limit was reached
+    Finished,
     Dead,
 }
 
+/// How the last game ended
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) enum RunOutcome {
+    #[default]
+    Died,
+    TimeUp,
+    TargetReached,
+}
+
+impl RunOutcome {
+    pub fn title(&self) -> &'static str {
+        match self {
+            RunOutcome::Died => "Game Over",
+            RunOutcome::TimeUp => "Time Up",
+            RunOutcome::TargetReached => "Target Reached",
+        }
+    }
+}
+
+fn reset_outcome(mut outcome: ResMut<RunOutcome>) {
+    *outcome = RunOutcome::default();
+}
+
+fn record_death(mut outcome: ResMut<RunOutcome>) {
+    *outcome = RunOutcome::Died;
+}
+
 fn init_state(mut next_state: ResMut<NextState<Playing>>) {
     next_state.set(Playing::Live);
 }
 
+/// How long the player has been alive this game
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct SurvivalTime(Stopwatch);
+
+fn reset_survival_time(mut survival_time: ResMut<SurvivalTime>) {
+    survival_time.reset();
+}
+
+fn tick_survival_time(time: Res<Time>, mut survival_time: ResMut<SurvivalTime>) {
+    survival_time.tick(time.delta());
+}
+
 #[derive(Deref, DerefMut)]
 struct DyingTimer(Timer);
 impl Default for DyingTimer {
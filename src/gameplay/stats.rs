@@ -0,0 +1,181 @@
+//! Statistics collected over a single run, for the score screen and balancing.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::Val::*};
+use serde::Serialize;
+
+use crate::{
+    gameplay::{
+        DespawnSet,
+        enemy::{EnemyType, ShapeCounts},
+        mode::{GameMode, RunSeed},
+        path::{LoopClosed, PathExtended, ShapeCrossedPath, ShapesCombined, ShapesExploded},
+        state::SurvivalTime,
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+pub(crate) struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(Screen::Gameplay), reset_stats)
+            .add_systems(
+                Update,
+                (
+                    record_loops,
+                    record_path_length,
+                    record_combines,
+                    record_explosions,
+                    record_crossings,
+                    record_peak_count.run_if(resource_changed::<ShapeCounts>),
+                )
+                    .run_if(in_state(Screen::Gameplay)),
+            )
+            .add_systems(
+                OnExit(Screen::Gameplay),
+                record_survival_time.before(DespawnSet),
+            );
+    }
+}
+
+#[derive(Resource, Default, Serialize)]
+pub(crate) struct RunStats {
+    pub mode: String,
+    pub seed: Option<u64>,
+    pub loops_drawn: usize,
+    /// loops that closed with no shapes inside
+    pub empty_loops: usize,
+    pub largest_loop_area: f32,
+    pub total_path_length: f32,
+    pub combines: CombineStats,
+    /// number of shapes ejected from loops
+    pub explosions: usize,
+    /// number of times a shape went through a sensor path
+    pub path_crossings: usize,
+    pub survival_time: f32,
+    pub peak_shape_count: usize,
+}
+
+/// Number of combines that produced each type
+#[derive(Default, Serialize)]
+pub(crate) struct CombineStats {
+    pub yellow: usize,
+    pub purple: usize,
+    pub cyan: usize,
+    pub white: usize,
+}
+
+fn reset_stats(mut commands: Commands, mode: Res<GameMode>, seed: Res<RunSeed>) {
+    commands.insert_resource(RunStats {
+        mode: mode.name().to_string(),
+        seed: seed.0,
+        ..default()
+    });
+}
+
+fn record_loops(mut loops: EventReader<LoopClosed>, mut stats: ResMut<RunStats>) {
+    for event in loops.read() {
+        stats.loops_drawn += 1;
+        if event.shapes == 0 {
+            stats.empty_loops += 1;
+        }
+        stats.largest_loop_area = stats.largest_loop_area.max(event.area);
+    }
+}
+
+fn record_path_length(mut extended: EventReader<PathExtended>, mut stats: ResMut<RunStats>) {
+    for event in extended.read() {
+        stats.total_path_length += event.length;
+    }
+}
+
+fn record_combines(mut combined: EventReader<ShapesCombined>, mut stats: ResMut<RunStats>) {
+    for event in combined.read() {
+        let count = match event.new_type {
+            EnemyType::Yellow => &mut stats.combines.yellow,
+            EnemyType::Purple => &mut stats.combines.purple,
+            EnemyType::Cyan => &mut stats.combines.cyan,
+            EnemyType::White => &mut stats.combines.white,
+            _ => continue,
+        };
+        *count += 1;
+    }
+}
+
+fn record_explosions(mut exploded: EventReader<ShapesExploded>, mut stats: ResMut<RunStats>) {
+    for event in exploded.read() {
+        stats.explosions += event.count;
+    }
+}
+
+fn record_crossings(mut crossed: EventReader<ShapeCrossedPath>, mut stats: ResMut<RunStats>) {
+    stats.path_crossings += crossed.read().count();
+}
+
+fn record_peak_count(counts: Res<ShapeCounts>, mut stats: ResMut<RunStats>) {
+    stats.peak_shape_count = stats.peak_shape_count.max(counts.total());
+}
+
+fn record_survival_time(survival_time: Res<SurvivalTime>, mut stats: ResMut<RunStats>) {
+    stats.survival_time = survival_time.elapsed_secs();
+}
+
+/// Summary of the run shown on the score screen
+pub(crate) fn stats_panel(stats: &RunStats) -> impl Bundle {
+    let rows = [
+        ("Survived", format!("{:.0}s", stats.survival_time)),
+        ("Loops drawn", stats.loops_drawn.to_string()),
+        ("Empty loops", stats.empty_loops.to_string()),
+        ("Largest loop", format!("{:.0}", stats.largest_loop_area)),
+        ("Path length", format!("{:.0}", stats.total_path_length)),
+        (
+            "Combines",
+            format!(
+                "{}Y {}P {}C {}W",
+                stats.combines.yellow,
+                stats.combines.purple,
+                stats.combines.cyan,
+                stats.combines.white
+            ),
+        ),
+        ("Explosions", stats.explosions.to_string()),
+        ("Most shapes", stats.peak_shape_count.to_string()),
+    ];
+    (
+        Name::new("Run Stats"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(4.0),
+            column_gap: Px(20.0),
+            grid_template_columns: RepeatedGridTrack::auto(2),
+            ..default()
+        },
+        Children::spawn(SpawnIter(
+            rows.into_iter()
+                .flat_map(|(name, value)| [name.to_string(), value])
+                .map(widget::label),
+        )),
+    )
+}
+
+/// Write the stats as json to the data directory
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn export(stats: &RunStats) {
+    let json = match serde_json::to_string_pretty(stats) {
+        Ok(json) => json,
+        Err(err) => {
+            warn!("Could not serialize run stats: {err}");
+            return;
+        }
+    };
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let file_name = format!("run_stats_{timestamp}.json");
+    match crate::storage::save(&file_name, &json) {
+        Ok(()) => info!("Saved run stats to {file_name}"),
+        Err(err) => warn!("Could not save run stats: {err}"),
+    }
+}
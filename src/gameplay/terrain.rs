@@ -0,0 +1,147 @@
+//! Terrain on the hex map and how it changes movement.
+
+use avian2d::prelude::{LinearDamping, LinearVelocity};
+use bevy::{color::palettes::tailwind, platform::collections::HashMap, prelude::*};
+use hexx::{Hex, HexLayout};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{enemy::Enemy, level::CurrentLevel},
+    screens::Screen,
+};
+
+pub(crate) struct TerrainPlugin;
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainMap>()
+            .add_systems(OnEnter(Screen::Gameplay), build_terrain_map)
+            .add_systems(
+                FixedUpdate,
+                apply_terrain_to_shapes.run_if(in_state(Screen::Gameplay)),
+            );
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) enum Terrain {
+    #[default]
+    Ground,
+    Grass,
+    /// slows everything down
+    Mud,
+    /// hard to steer on
+    Ice,
+    /// speeds things up in the direction they are moving
+    Boost,
+    /// impassable
+    Wall,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 6] = [
+        Terrain::Ground,
+        Terrain::Grass,
+        Terrain::Mud,
+        Terrain::Ice,
+        Terrain::Boost,
+        Terrain::Wall,
+    ];
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn color(&self) -> Color {
+        Color::Srgba(match self {
+            Terrain::Ground => tailwind::GRAY_500,
+            Terrain::Grass => tailwind::GREEN_800,
+            Terrain::Mud => tailwind::AMBER_900,
+            Terrain::Ice => tailwind::SKY_200,
+            Terrain::Boost => tailwind::FUCHSIA_600,
+            Terrain::Wall => tailwind::STONE_900,
+        })
+    }
+
+    /// multiplier for the player's friction
+    pub fn friction(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.5,
+            Terrain::Mud => 4.0,
+            Terrain::Ice => 0.05,
+            _ => 1.0,
+        }
+    }
+
+    /// multiplier for the player's forward acceleration
+    pub fn traction(&self) -> f32 {
+        match self {
+            Terrain::Mud => 0.5,
+            Terrain::Ice => 0.3,
+            _ => 1.0,
+        }
+    }
+
+    /// linear damping applied to shapes
+    pub fn damping(&self) -> f32 {
+        match self {
+            Terrain::Grass => 0.3,
+            Terrain::Mud => 3.0,
+            _ => 0.0,
+        }
+    }
+
+    /// acceleration in the direction of travel
+    pub fn boost(&self) -> f32 {
+        match self {
+            Terrain::Boost => 600.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Terrain of every hex in the current level, for quick lookups
+#[derive(Resource, Default)]
+pub(crate) struct TerrainMap {
+    layout: HexLayout,
+    default: Terrain,
+    tiles: HashMap<Hex, Terrain>,
+}
+
+impl TerrainMap {
+    pub fn at(&self, position: Vec2) -> Terrain {
+        let hex = self.layout.world_pos_to_hex(position);
+        self.tiles.get(&hex).copied().unwrap_or(self.default)
+    }
+}
+
+fn build_terrain_map(mut commands: Commands, level: CurrentLevel) {
+    let map = &level.get().map;
+    commands.insert_resource(TerrainMap {
+        layout: map.layout(),
+        default: map.terrain,
+        tiles: map
+            .tiles
+            .iter()
+            .map(|tile| (Hex::from(tile.hex), tile.terrain))
+            .collect(),
+    });
+}
+
+fn apply_terrain_to_shapes(
+    time: Res<Time>,
+    terrain: Res<TerrainMap>,
+    mut shapes: Query<(&Transform, &mut LinearDamping, &mut LinearVelocity), With<Enemy>>,
+) {
+    for (transform, mut damping, mut velocity) in &mut shapes {
+        let terrain = terrain.at(transform.translation.truncate());
+        if damping.0 != terrain.damping() {
+            damping.0 = terrain.damping();
+        }
+        let boost = terrain.boost();
+        if boost != 0.0 {
+            let direction = velocity.normalize_or_zero();
+            velocity.0 += direction * boost * time.delta_secs();
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Territory mode, where closed loops claim the hexes inside them.
+
+use bevy::{
+    input::common_conditions::input_just_pressed, platform::collections::HashMap, prelude::*,
+};
+use hexx::Hex;
+
+use crate::{
+    PausableSystems,
+    gameplay::{
+        containment,
+        enemy::{Enemy, EnemyType},
+        layers,
+        level::CurrentLevel,
+        map::Map,
+        mode::GameMode,
+        path::LoopClosed,
+        score::{LiveScore, ScoreRules},
+        state::Playing,
+    },
+    screens::Screen,
+};
+
+pub(crate) struct TerritoryPlugin;
+impl Plugin for TerritoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Territory>()
+            .init_resource::<TerritoryHandles>()
+            .add_systems(OnEnter(Screen::Gameplay), reset_territory)
+            .add_systems(
+                Update,
+                (
+                    cycle_pen_color.run_if(input_just_pressed(KeyCode::Tab)),
+                    (paint_loops, contest_tiles).chain(),
+                )
+                    .in_set(PausableSystems)
+                    .run_if(resource_exists_and_equals(GameMode::Territory))
+                    .run_if(in_state(Screen::Gameplay))
+                    .run_if(in_state(Playing::Live)),
+            )
+            .add_systems(OnEnter(Playing::Finished), score_territory)
+            .add_systems(OnEnter(Playing::Dying), score_territory);
+    }
+}
+
+/// Colour a pen paints territory with
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct PenColor(pub EnemyType);
+
+impl Default for PenColor {
+    fn default() -> Self {
+        Self(EnemyType::Red)
+    }
+}
+
+/// Pens can paint in these
+const PEN_COLORS: [EnemyType; 3] = [EnemyType::Red, EnemyType::Green, EnemyType::Blue];
+
+/// Hexes claimed by loops this game
+#[derive(Resource, Default)]
+pub(crate) struct Territory {
+    claims: HashMap<Hex, Claim>,
+}
+
+struct Claim {
+    color: EnemyType,
+    /// a shape of another colour is sitting on the hex
+    contested: bool,
+    tile: Entity,
+}
+
+impl Territory {
+    pub fn claimed(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn contested(&self) -> usize {
+        self.claims.values().filter(|claim| claim.contested).count()
+    }
+
+    /// claimed hexes that count toward the score
+    pub fn held(&self) -> usize {
+        self.claimed() - self.contested()
+    }
+}
+
+#[derive(Resource)]
+struct TerritoryHandles {
+    claimed: HashMap<EnemyType, Handle<ColorMaterial>>,
+    contested: HashMap<EnemyType, Handle<ColorMaterial>>,
+}
+
+impl TerritoryHandles {
+    fn material(&self, color: EnemyType, contested: bool) -> Handle<ColorMaterial> {
+        let materials = if contested {
+            &self.contested
+        } else {
+            &self.claimed
+        };
+        materials[&color].clone()
+    }
+}
+
+impl FromWorld for TerritoryHandles {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        let mut tinted = |alpha: f32| {
+            PEN_COLORS
+                .into_iter()
+                .map(|t| (t, materials.add(t.color().with_alpha(alpha))))
+                .collect::<HashMap<_, _>>()
+        };
+        Self {
+            claimed: tinted(0.45),
+            contested: tinted(0.15),
+        }
+    }
+}
+
+fn reset_territory(mut commands: Commands) {
+    // the tiles themselves are scoped to the screen
+    commands.insert_resource(Territory::default());
+}
+
+fn cycle_pen_color(mut pens: Query<&mut PenColor>) {
+    for mut pen in &mut pens {
+        let index = PEN_COLORS
+            .iter()
+            .position(|t| *t == pen.0)
+            .unwrap_or_default();
+        pen.0 = PEN_COLORS[(index + 1) % PEN_COLORS.len()];
+    }
+}
+
+/// claim every open hex whose center is inside a closed loop
+fn paint_loops(
+    mut commands: Commands,
+    mut loops: EventReader<LoopClosed>,
+    pens: Query<&PenColor>,
+    level: CurrentLevel,
+    map: Res<Map>,
+    handles: Res<TerritoryHandles>,
+    mut territory: ResMut<Territory>,
+) {
+    let level_map = &level.get().map;
+    for event in loops.read() {
+        let color = pens.get(event.pen).copied().unwrap_or_default().0;
+        for hex in level_map.hexes().filter(|hex| !level_map.is_wall(*hex)) {
+            let center = map.layout.hex_to_world_pos(hex);
+            if !containment::contains_point(&event.points, center) {
+                continue;
+            }
+            match territory.claims.get_mut(&hex) {
+                Some(claim) if claim.color == color => {}
+                Some(claim) => {
+                    claim.color = color;
+                    claim.contested = false;
+                    commands
+                        .entity(claim.tile)
+                        .insert(MeshMaterial2d(handles.material(color, false)));
+                }
+                None => {
+                    let tile = commands
+                        .spawn((
+                            Name::new("Territory Hex"),
+                            Mesh2d(map.mesh_handle.clone()),
+                            MeshMaterial2d(handles.material(color, false)),
+                            Transform::from_translation(center.extend(layers::MAP + 0.2)),
+                            StateScoped(Screen::Gameplay),
+                        ))
+                        .id();
+                    territory.claims.insert(
+                        hex,
+                        Claim {
+                            color,
+                            contested: false,
+                            tile,
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// claims with a shape of another colour on them don't count
+fn contest_tiles(
+    mut commands: Commands,
+    map: Res<Map>,
+    handles: Res<TerritoryHandles>,
+    shapes: Query<(&Transform, &EnemyType), With<Enemy>>,
+    mut territory: ResMut<Territory>,
+) {
+    let mut occupants = HashMap::<Hex, Vec<EnemyType>>::default();
+    for (transform, enemy_type) in &shapes {
+        let hex = map
+            .layout
+            .world_pos_to_hex(transform.translation.truncate());
+        occupants.entry(hex).or_default().push(*enemy_type);
+    }
+
+    // only touch the resource when something changes so the HUD isn't updated every frame
+    let changed = territory
+        .bypass_change_detection()
+        .claims
+        .iter_mut()
+        .filter_map(|(hex, claim)| {
+            let contested = occupants
+                .get(hex)
+                .is_some_and(|types| types.iter().any(|t| *t != claim.color));
+            if contested == claim.contested {
+                return None;
+            }
+            claim.contested = contested;
+            commands
+                .entity(claim.tile)
+                .insert(MeshMaterial2d(handles.material(claim.color, contested)));
+            Some(())
+        })
+        .count();
+    if changed > 0 {
+        territory.set_changed();
+    }
+}
+
+fn score_territory(
+    mode: Res<GameMode>,
+    rules: Res<ScoreRules>,
+    territory: Res<Territory>,
+    mut score: ResMut<LiveScore>,
+) {
+    if *mode == GameMode::Territory {
+        score.points += rules.claimed_hex * territory.held();
+    }
+}
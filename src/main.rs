@@ -10,6 +10,7 @@ mod dev_tools;
 mod gameplay;
 mod menus;
 mod screens;
+mod storage;
 mod theme;
 
 use avian2d::{PhysicsPlugins, prelude::Gravity};
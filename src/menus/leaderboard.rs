@@ -0,0 +1,78 @@
+//! The high score leaderboard menu.
+
+use bevy::{
+    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{
+    gameplay::highscore::{HighScoreEntry, HighScores},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Leaderboard), spawn_leaderboard_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Leaderboard).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+fn spawn_leaderboard_menu(mut commands: Commands, high_scores: Res<HighScores>) {
+    commands
+        .spawn((
+            widget::ui_root("Leaderboard Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Leaderboard),
+            children![widget::header("High Scores")],
+        ))
+        .with_children(|parent| {
+            let mut empty = true;
+            for (key, entries) in high_scores.tables() {
+                empty = false;
+                parent.spawn(widget::label(key.name()));
+                parent.spawn(grid(entries));
+            }
+            if empty {
+                parent.spawn(widget::label("No scores yet"));
+            }
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+fn grid(entries: &[HighScoreEntry]) -> impl Bundle {
+    let rows = entries
+        .iter()
+        .enumerate()
+        .flat_map(|(i, entry)| {
+            [
+                format!("{}.", i + 1),
+                entry.name.clone(),
+                entry.points.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(4.0),
+            column_gap: Px(30.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::px(1, 40.0),
+                RepeatedGridTrack::px(1, 200.0),
+                RepeatedGridTrack::px(1, 100.0),
+            ],
+            ..default()
+        },
+        Children::spawn(SpawnIter(rows.into_iter().map(widget::label))),
+    )
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -1,14 +1,39 @@
 //! The main menu (seen on the title screen).
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    gameplay::{
+        arena::{self, Arena, ArenaSeed},
+        level::CurrentLevel,
+        mode::GameMode,
+    },
+    menus::Menu,
+    screens::{AfterLoading, Screen},
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
+    app.add_systems(
+        Update,
+        (
+            update_game_mode_label.run_if(resource_changed::<GameMode>),
+            update_arena_label.run_if(resource_changed::<Arena>),
+            update_seed_widget.run_if(resource_changed::<Arena>.or(resource_changed::<ArenaSeed>)),
+        )
+            .run_if(in_state(Menu::Main)),
+    );
 }
 
-fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mode: Res<GameMode>,
+    arena: Res<Arena>,
+    seed: Res<ArenaSeed>,
+) {
     commands.spawn((
         widget::ui_root("Main Menu"),
         GlobalZIndex(2),
@@ -16,6 +41,11 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         #[cfg(not(target_family = "wasm"))]
         children![
             widget::button("Play", enter_loading_or_gameplay_screen),
+            game_mode_widget(*mode),
+            arena_widget(*arena),
+            seed_widget(*arena, *seed),
+            widget::button("High Scores", open_leaderboard_menu),
+            widget::button("Level Editor", enter_editor_screen),
             widget::button("Settings", open_settings_menu),
             widget::button("Exit", exit_app),
             rules(asset_server.load("images/rules.png")),
@@ -23,11 +53,144 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         #[cfg(target_family = "wasm")]
         children![
             widget::button("Play", enter_loading_or_gameplay_screen),
+            game_mode_widget(*mode),
+            arena_widget(*arena),
+            seed_widget(*arena, *seed),
+            widget::button("High Scores", open_leaderboard_menu),
+            widget::button("Level Editor", enter_editor_screen),
             widget::button("Settings", open_settings_menu),
         ],
     ));
 }
 
+fn game_mode_widget(mode: GameMode) -> impl Bundle {
+    (
+        Name::new("Game Mode Widget"),
+        Node {
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            widget::button_small("<", previous_game_mode),
+            (
+                Name::new("Current Game Mode"),
+                Node {
+                    width: Px(200.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(mode.name()), GameModeLabel)],
+            ),
+            widget::button_small(">", next_game_mode),
+        ],
+    )
+}
+
+#[derive(Component)]
+struct GameModeLabel;
+
+fn previous_game_mode(_: Trigger<Pointer<Click>>, mut mode: ResMut<GameMode>) {
+    *mode = mode.previous();
+}
+
+fn next_game_mode(_: Trigger<Pointer<Click>>, mut mode: ResMut<GameMode>) {
+    *mode = mode.next();
+}
+
+fn update_game_mode_label(mode: Res<GameMode>, mut label: Single<&mut Text, With<GameModeLabel>>) {
+    label.0 = mode.name().to_string();
+}
+
+fn arena_widget(arena: Arena) -> impl Bundle {
+    (
+        Name::new("Arena Widget"),
+        Node {
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            widget::button_small("<", next_arena),
+            (
+                Name::new("Current Arena"),
+                Node {
+                    width: Px(200.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(arena.name()), ArenaLabel)],
+            ),
+            widget::button_small(">", next_arena),
+        ],
+    )
+}
+
+#[derive(Component)]
+struct ArenaLabel;
+
+fn next_arena(_: Trigger<Pointer<Click>>, mut arena: ResMut<Arena>) {
+    *arena = arena.next();
+}
+
+fn update_arena_label(arena: Res<Arena>, mut label: Single<&mut Text, With<ArenaLabel>>) {
+    label.0 = arena.name().to_string();
+}
+
+/// Seed of the generated arena, only shown when the arena is generated
+fn seed_widget(arena: Arena, seed: ArenaSeed) -> impl Bundle {
+    (
+        Name::new("Seed Widget"),
+        Node {
+            display: seed_display(arena),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        SeedWidget,
+        children![
+            (
+                Name::new("Current Seed"),
+                Node {
+                    width: Px(260.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(seed_text(seed)), SeedLabel)],
+            ),
+            widget::button_small(">", reroll_seed),
+        ],
+    )
+}
+
+#[derive(Component)]
+struct SeedWidget;
+
+#[derive(Component)]
+struct SeedLabel;
+
+fn seed_display(arena: Arena) -> Display {
+    match arena {
+        Arena::Generated => Display::Flex,
+        Arena::Level => Display::None,
+    }
+}
+
+fn seed_text(seed: ArenaSeed) -> String {
+    format!("Seed {}", seed.0)
+}
+
+fn reroll_seed(_: Trigger<Pointer<Click>>, mut seed: ResMut<ArenaSeed>) {
+    seed.reroll();
+}
+
+fn update_seed_widget(
+    arena: Res<Arena>,
+    seed: Res<ArenaSeed>,
+    mut widget: Single<&mut Node, With<SeedWidget>>,
+    mut label: Single<&mut Text, With<SeedLabel>>,
+) {
+    widget.display = seed_display(*arena);
+    label.0 = seed_text(*seed);
+}
+
 fn rules(image: Handle<Image>) -> impl Bundle {
     (
         ImageNode { image, ..default() },
@@ -39,18 +202,36 @@ fn rules(image: Handle<Image>) -> impl Bundle {
     )
 }
 
-fn enter_loading_or_gameplay_screen(
-    _: Trigger<Pointer<Click>>,
+fn enter_loading_or_gameplay_screen(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    // the arena picks the level, so only check what is loaded once it has run
+    commands.run_system_cached(arena::prepare_arena);
+    commands.run_system_cached_with(enter_when_loaded, Screen::Gameplay);
+}
+
+fn enter_editor_screen(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached_with(enter_when_loaded, Screen::Editor);
+}
+
+/// Go to `screen`, through the loading screen if its assets or level aren't ready yet
+fn enter_when_loaded(
+    In(screen): In<Screen>,
     resource_handles: Res<ResourceHandles>,
+    level: CurrentLevel,
+    mut after_loading: ResMut<AfterLoading>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Gameplay);
+    if resource_handles.is_all_done() && level.is_ready() {
+        next_screen.set(screen);
     } else {
+        after_loading.0 = screen;
         next_screen.set(Screen::Loading);
     }
 }
 
+fn open_leaderboard_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Leaderboard);
+}
+
 fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
@@ -1,6 +1,7 @@
 //! The game's menus and transitions between them.
 
 mod credits;
+mod leaderboard;
 mod main;
 mod pause;
 mod settings;
@@ -12,6 +13,7 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_plugins((
         credits::plugin,
+        leaderboard::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
@@ -25,6 +27,7 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    Leaderboard,
     Settings,
     Pause,
 }
@@ -4,7 +4,9 @@
 
 use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    gameplay::containment::ContainmentSettings, menus::Menu, screens::Screen, theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -16,24 +18,28 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<GlobalVolumeLabel>();
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_global_volume_label,
+            update_borderline_label.run_if(resource_changed::<ContainmentSettings>),
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
-fn spawn_settings_menu(mut commands: Commands) {
+fn spawn_settings_menu(mut commands: Commands, containment: Res<ContainmentSettings>) {
     commands.spawn((
         widget::ui_root("Settings Menu"),
         GlobalZIndex(2),
         StateScoped(Menu::Settings),
         children![
             widget::header("Settings"),
-            settings_grid(),
+            settings_grid(&containment),
             widget::button("Back", go_back_on_click),
         ],
     ));
 }
 
-fn settings_grid() -> impl Bundle {
+fn settings_grid(containment: &ContainmentSettings) -> impl Bundle {
     (
         Name::new("Settings Grid"),
         Node {
@@ -52,6 +58,14 @@ fn settings_grid() -> impl Bundle {
                 }
             ),
             global_volume_widget(),
+            (
+                widget::label("Shapes On The Line"),
+                Node {
+                    justify_self: JustifySelf::End,
+                    ..default()
+                }
+            ),
+            borderline_widget(containment),
         ],
     )
 }
@@ -104,6 +118,49 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+fn borderline_widget(containment: &ContainmentSettings) -> impl Bundle {
+    (
+        Name::new("Borderline Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            (
+                Name::new("Current Borderline Action"),
+                Node {
+                    width: Px(160.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(
+                    widget::label(containment.borderline_action.name()),
+                    BorderlineLabel
+                )],
+            ),
+            widget::button_small(">", next_borderline_action),
+        ],
+    )
+}
+
+#[derive(Component)]
+struct BorderlineLabel;
+
+fn next_borderline_action(
+    _: Trigger<Pointer<Click>>,
+    mut containment: ResMut<ContainmentSettings>,
+) {
+    containment.borderline_action = containment.borderline_action.next();
+}
+
+fn update_borderline_label(
+    containment: Res<ContainmentSettings>,
+    mut label: Single<&mut Text, With<BorderlineLabel>>,
+) {
+    label.0 = containment.borderline_action.name().to_string();
+}
+
 fn go_back_on_click(
     _: Trigger<Pointer<Click>>,
     screen: Res<State<Screen>>,
@@ -3,17 +3,35 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::{
+    asset_tracking::ResourceHandles, gameplay::level::level_ready, screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AfterLoading>();
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        enter_next_screen.run_if(
+            in_state(Screen::Loading)
+                .and(all_assets_loaded)
+                .and(level_ready),
+        ),
     );
 }
 
+/// The screen to go to once everything is loaded.
+#[derive(Resource, Clone, Copy)]
+pub struct AfterLoading(pub Screen);
+
+impl Default for AfterLoading {
+    fn default() -> Self {
+        Self(Screen::Gameplay)
+    }
+}
+
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
@@ -22,8 +40,8 @@ fn spawn_loading_screen(mut commands: Commands) {
     ));
 }
 
-fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
-    next_screen.set(Screen::Gameplay);
+fn enter_next_screen(after: Res<AfterLoading>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(after.0);
 }
 
 fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
@@ -7,6 +7,8 @@ mod title;
 
 use bevy::prelude::*;
 
+pub use loading::AfterLoading;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
 
@@ -28,4 +30,5 @@ pub enum Screen {
     Loading,
     Gameplay,
     Score,
+    Editor,
 }
@@ -0,0 +1,56 @@
+//! Reading and writing files in the platform data directory.
+//!
+//! Web builds have no data directory, so nothing is persisted there.
+
+#[cfg(not(target_family = "wasm"))]
+use std::path::PathBuf;
+
+/// Folder inside the platform data directory that all of the game's files live in.
+#[cfg(not(target_family = "wasm"))]
+const APP_DIR: &str = "shape_herd";
+
+/// The game's data directory, e.g. `~/.local/share/shape_herd` on Linux.
+#[cfg(not(target_family = "wasm"))]
+pub fn data_dir() -> Option<PathBuf> {
+    use std::env::var_os;
+
+    #[cfg(target_os = "windows")]
+    let base = var_os("APPDATA").map(PathBuf::from);
+    #[cfg(target_os = "macos")]
+    let base = var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+
+    base.map(|base| base.join(APP_DIR))
+}
+
+/// Read a file from the data directory. Returns `None` if it doesn't exist yet.
+#[cfg(not(target_family = "wasm"))]
+pub fn load(file_name: &str) -> Option<String> {
+    std::fs::read_to_string(data_dir()?.join(file_name)).ok()
+}
+
+#[cfg(target_family = "wasm")]
+pub fn load(_file_name: &str) -> Option<String> {
+    None
+}
+
+/// Write a file to the data directory, creating the directory if needed.
+#[cfg(not(target_family = "wasm"))]
+pub fn save(file_name: &str, contents: &str) -> std::io::Result<()> {
+    let Some(dir) = data_dir() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no data directory on this platform",
+        ));
+    };
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(file_name), contents)
+}
+
+#[cfg(target_family = "wasm")]
+pub fn save(_file_name: &str, _contents: &str) -> std::io::Result<()> {
+    Ok(())
+}